@@ -0,0 +1,75 @@
+use std::io::{IsTerminal, Write, stderr, stdin};
+
+use inf1_pp_flatslab_core::keys::SLAB_ID;
+use solana_pubkey::Pubkey;
+
+use crate::{
+    err::{CliErr, CliResult},
+    rpc::Rpc,
+    sscu::TxSendMode,
+};
+
+/// What a mutating command is about to send, printed before asking for confirmation.
+/// The changes themselves should be printed by the caller beforehand.
+#[derive(Clone, Copy)]
+pub struct SendSummary<'a> {
+    pub rpc: &'a dyn Rpc,
+    pub signers: &'a [Pubkey],
+    pub n_txs: usize,
+}
+
+impl SendSummary<'_> {
+    /// Prints the summary then waits for the user to confirm, erring if they do not.
+    ///
+    /// Does nothing if transactions are not actually going to be sent.
+    /// Skips the prompt if `yes`, otherwise refuses to run if stdin is not a terminal.
+    pub fn confirm(&self, send_mode: TxSendMode, yes: bool) -> CliResult<()> {
+        if !matches!(
+            send_mode,
+            TxSendMode::SendActual | TxSendMode::Squads | TxSendMode::Realms
+        ) {
+            return Ok(());
+        }
+        let Self {
+            rpc,
+            signers,
+            n_txs,
+        } = self;
+
+        let mut signers: Vec<_> = signers.to_vec();
+        signers.dedup();
+
+        eprintln!("Cluster: {}", rpc.url());
+        eprintln!("Program: {}", Pubkey::new_from_array(crate::PROGRAM_ID));
+        eprintln!("Slab: {}", Pubkey::new_from_array(SLAB_ID));
+        eprintln!(
+            "Signers: {}",
+            signers
+                .iter()
+                .map(|s| s.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        eprintln!("Transactions: {n_txs}");
+
+        if yes {
+            return Ok(());
+        }
+        if !stdin().is_terminal() {
+            return Err(CliErr::Aborted(
+                "Refusing to send transactions non-interactively, rerun with --yes".to_owned(),
+            ));
+        }
+
+        eprint!("Send? [y/N] ");
+        let mut answer = String::new();
+        stderr()
+            .flush()
+            .and_then(|_| stdin().read_line(&mut answer))
+            .map_err(|e| CliErr::Aborted(format!("Failed to read confirmation: {e}")))?;
+        if !matches!(answer.trim().to_lowercase().as_str(), "y" | "yes") {
+            return Err(CliErr::Aborted("Aborted".to_owned()));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,229 @@
+use inf1_pp_flatslab_core::{keys::SLAB_ID, typedefs::SlabEntryPacked};
+use solana_account_decoder_client_types::UiAccountEncoding;
+use solana_instruction::Instruction;
+use solana_pubkey::Pubkey;
+use solana_rpc_client_types::config::{
+    RpcSimulateTransactionAccountsConfig, RpcSimulateTransactionConfig,
+};
+
+use crate::{
+    err::{CliErr, CliResult},
+    rpc::Rpc,
+    sscu::TxSendMode,
+    utils::{SIM_TX_CFG, to_est_cu_sim_tx},
+};
+
+/// Size of a single entry in the slab's account data
+pub const SLAB_ENTRY_SIZE: usize = size_of::<SlabEntryPacked>();
+
+const LAMPORTS_PER_SIGNATURE: u64 = 5_000;
+
+/// Lamports a command is expected to cost its payer
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct LamportCosts {
+    /// Rent paid for growing the slab, before any refunds
+    pub rent_paid: u64,
+
+    /// Rent refunded from shrinking the slab
+    pub rent_refunded: u64,
+
+    /// Base and compute budget fees across all transactions
+    pub tx_fees: u64,
+}
+
+impl LamportCosts {
+    /// Lamports the payer must have upfront.
+    /// Refunds are not counted since they may only come after all rent has been paid.
+    pub const fn required(&self) -> u64 {
+        self.rent_paid + self.tx_fees
+    }
+
+    /// Prints costs and the balances of their payers.
+    /// `fee_payer` pays transaction fees and `rent_payer` pays rent.
+    /// They differ in proposal modes, where the proposer's signer sends the transactions
+    /// but rent is paid by the proposal's executor once it executes.
+    ///
+    /// Errs if the fee payer cannot afford what it pays and transactions are actually going to be sent.
+    /// A rent payer that is not the fee payer is only warned about
+    /// since it only pays once the proposal executes.
+    pub async fn check_payers(
+        &self,
+        rpc: &dyn Rpc,
+        fee_payer: &Pubkey,
+        rent_payer: &Pubkey,
+        send_mode: TxSendMode,
+    ) -> CliResult<()> {
+        let Self {
+            rent_paid,
+            rent_refunded,
+            tx_fees,
+        } = self;
+
+        eprintln!("Rent paid: {rent_paid} lamports");
+        eprintln!("Rent refunded: {rent_refunded} lamports");
+        eprintln!("Transaction fees: {tx_fees} lamports");
+
+        let payers = if fee_payer == rent_payer {
+            vec![(fee_payer, self.required())]
+        } else {
+            vec![(fee_payer, *tx_fees), (rent_payer, *rent_paid)]
+        };
+        for (payer, required) in payers {
+            let balance = rpc.get_balance(payer).await?;
+            eprintln!("Payer {payer} balance: {balance} lamports");
+            if balance >= required {
+                continue;
+            }
+            let msg = format!("Payer {payer} balance insufficient, requires {required} lamports");
+            let sent = matches!(
+                send_mode,
+                TxSendMode::SendActual | TxSendMode::Squads | TxSendMode::Realms
+            );
+            if sent && payer == fee_payer {
+                return Err(CliErr::Aborted(msg));
+            }
+            eprintln!("Warning: {msg}");
+        }
+        Ok(())
+    }
+}
+
+/// Fees of `n_sigs` signatures summed over each transaction,
+/// plus at most `fee_cb` compute budget fees if `send_mode` adds compute budget instructions
+pub fn tx_fees(n_sigs: impl IntoIterator<Item = usize>, send_mode: TxSendMode, fee_cb: u64) -> u64 {
+    let cb_fee = match send_mode {
+        TxSendMode::SendActual | TxSendMode::SimOnly | TxSendMode::Squads | TxSendMode::Realms => {
+            fee_cb
+        }
+        TxSendMode::Dump64 | TxSendMode::Dump58 | TxSendMode::DumpMsg | TxSendMode::DumpIxs => 0,
+    };
+    n_sigs
+        .into_iter()
+        .map(|n| n as u64 * LAMPORTS_PER_SIGNATURE + cb_fee)
+        .sum()
+}
+
+/// Returns (rent paid, rent refunded) for a slab that starts at `curr_len` bytes,
+/// has `n_added` entries added, then `n_removed` entries removed
+pub async fn slab_rent_delta(
+    rpc: &dyn Rpc,
+    curr_len: usize,
+    n_added: usize,
+    n_removed: usize,
+) -> CliResult<(u64, u64)> {
+    let peak_len = curr_len + n_added * SLAB_ENTRY_SIZE;
+    let final_len = peak_len - n_removed * SLAB_ENTRY_SIZE;
+    let mut rents = [0u64; 3];
+    for (len, rent) in [curr_len, peak_len, final_len]
+        .into_iter()
+        .zip(rents.iter_mut())
+    {
+        *rent = rpc.get_minimum_balance_for_rent_exemption(len).await?;
+    }
+    let [curr, peak, fin] = rents;
+    Ok((peak - curr, peak - fin))
+}
+
+/// Simulates `ixs` and returns the slab account's resulting lamports,
+/// or `None` if simulation failed
+pub async fn sim_slab_lamports(
+    rpc: &dyn Rpc,
+    payer_pk: &Pubkey,
+    ixs: &[Instruction],
+) -> CliResult<Option<u64>> {
+    let result = rpc
+        .simulate_transaction_with_config(
+            &to_est_cu_sim_tx(payer_pk, ixs, &[]),
+            RpcSimulateTransactionConfig {
+                accounts: Some(RpcSimulateTransactionAccountsConfig {
+                    encoding: Some(UiAccountEncoding::Base64),
+                    addresses: vec![Pubkey::new_from_array(SLAB_ID).to_string()],
+                }),
+                ..SIM_TX_CFG
+            },
+        )
+        .await?;
+    if result.value.err.is_some() {
+        return Ok(None);
+    }
+    Ok(result
+        .value
+        .accounts
+        .and_then(|accs| accs.into_iter().next().flatten())
+        .map(|acc| acc.lamports))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::rpc::mock::MockRpc;
+
+    use super::*;
+
+    #[test]
+    fn tx_fees_cb_only_when_sent() {
+        assert_eq!(tx_fees([1, 2], TxSendMode::SendActual, 100), 15_200);
+        assert_eq!(tx_fees([1, 2], TxSendMode::Dump64, 100), 15_000);
+        assert_eq!(tx_fees([], TxSendMode::SendActual, 100), 0);
+    }
+
+    #[tokio::test]
+    async fn check_payers_fee_payer_only_errs() {
+        let [fee_payer, vault] = core::array::from_fn(|_| Pubkey::new_unique());
+        let costs = LamportCosts {
+            rent_paid: 1_000,
+            rent_refunded: 0,
+            tx_fees: 10,
+        };
+        let check = |fee_payer_balance, vault_balance, send_mode| {
+            let rpc = MockRpc::default()
+                .with_balance(fee_payer, fee_payer_balance)
+                .with_balance(vault, vault_balance);
+            async move {
+                costs
+                    .check_payers(&rpc, &fee_payer, &vault, send_mode)
+                    .await
+            }
+        };
+
+        // vault only pays rent once the proposal executes
+        assert!(check(10, 0, TxSendMode::Squads).await.is_ok());
+        assert!(matches!(
+            check(9, 1_000, TxSendMode::Squads).await,
+            Err(CliErr::Aborted(_))
+        ));
+        assert!(check(9, 1_000, TxSendMode::Dump64).await.is_ok());
+
+        // a single payer pays both
+        let rpc = MockRpc::default().with_balance(fee_payer, 1_009);
+        assert!(matches!(
+            costs
+                .check_payers(&rpc, &fee_payer, &fee_payer, TxSendMode::SendActual)
+                .await,
+            Err(CliErr::Aborted(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn slab_rent_delta_add_then_remove() {
+        let rpc = MockRpc::default();
+        // mock rent is linear in len
+        let entry_rent = rpc
+            .get_minimum_balance_for_rent_exemption(SLAB_ENTRY_SIZE)
+            .await
+            .unwrap()
+            - rpc.get_minimum_balance_for_rent_exemption(0).await.unwrap();
+
+        assert_eq!(
+            slab_rent_delta(&rpc, 100, 3, 1).await.unwrap(),
+            (3 * entry_rent, entry_rent)
+        );
+        // peak is the current len if nothing is added
+        assert_eq!(
+            slab_rent_delta(&rpc, 100 + 2 * SLAB_ENTRY_SIZE, 0, 2)
+                .await
+                .unwrap(),
+            (0, 2 * entry_rent)
+        );
+        assert_eq!(slab_rent_delta(&rpc, 100, 0, 0).await.unwrap(), (0, 0));
+    }
+}
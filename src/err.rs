@@ -0,0 +1,67 @@
+use std::fmt::{Display, Formatter};
+
+use solana_rpc_client_api::client_error::Error as ClientError;
+
+/// Errors that end a command, categorized so that scripts can react to the exit code.
+///
+/// | Code | Category |
+/// |------|----------|
+/// | 1 | Aborted: declined confirmation or insufficient payer balance |
+/// | 2 | Config: solana config, signers or missing flags |
+/// | 3 | Input: invalid files or arguments, including ones rejected by argument parsing |
+/// | 4 | Rpc: RPC request failed |
+/// | 5 | Sim: transaction failed simulation |
+/// | 6 | Onchain: transaction failed onchain or did not land |
+/// | 7 | Verify: prices or slab state failed verification |
+/// | 8 | Diff: `diff` found changes |
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum CliErr {
+    Aborted(String),
+    Config(String),
+    Input(String),
+    Rpc(String),
+    Sim(String),
+    Onchain(String),
+    Verify(String),
+    Diff(String),
+}
+
+pub type CliResult<T> = Result<T, CliErr>;
+
+impl CliErr {
+    pub const fn exit_code(&self) -> i32 {
+        match self {
+            Self::Aborted(_) => 1,
+            Self::Config(_) => 2,
+            Self::Input(_) => 3,
+            Self::Rpc(_) => 4,
+            Self::Sim(_) => 5,
+            Self::Onchain(_) => 6,
+            Self::Verify(_) => 7,
+            Self::Diff(_) => 8,
+        }
+    }
+}
+
+impl Display for CliErr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Aborted(s)
+            | Self::Config(s)
+            | Self::Input(s)
+            | Self::Rpc(s)
+            | Self::Sim(s)
+            | Self::Onchain(s)
+            | Self::Verify(s)
+            | Self::Diff(s) => f.write_str(s),
+        }
+    }
+}
+
+impl std::error::Error for CliErr {}
+
+impl From<ClientError> for CliErr {
+    fn from(e: ClientError) -> Self {
+        Self::Rpc(format!("RPC request failed: {e}"))
+    }
+}
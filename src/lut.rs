@@ -0,0 +1,146 @@
+//! Minimal address lookup table program client.
+//! Hand-rolled since solana-address-lookup-table-interface is not worth the extra dep for 2 instructions.
+
+use clap::{Args, builder::ValueParser};
+use solana_instruction::{AccountMeta, Instruction};
+use solana_message::AddressLookupTableAccount;
+use solana_pubkey::Pubkey;
+
+use crate::{
+    err::{CliErr, CliResult},
+    rpc::Rpc,
+    sscu::parse_pubkey_from_src,
+};
+
+pub const LUT_PROGRAM_ID: Pubkey =
+    Pubkey::from_str_const("AddressLookupTab1e1111111111111111111111111");
+
+const SYSTEM_PROGRAM_ID: Pubkey = Pubkey::new_from_array([0u8; 32]);
+
+/// type: u32, deactivation_slot: u64, last_extended_slot: u64,
+/// last_extended_slot_start_index: u8, authority: Option<Pubkey>, padding: u16
+const LOOKUP_TABLE_META_SIZE: usize = 56;
+
+/// `ProgramState::LookupTable`
+const LOOKUP_TABLE_STATE: [u8; 4] = [1, 0, 0, 0];
+
+/// Max number of addresses to add in a single extend instruction
+/// such that the transaction stays under the size limit
+pub const MAX_EXTEND_ADDRESSES: usize = 20;
+
+#[derive(Args, Debug)]
+pub struct UseLutArgs {
+    #[arg(
+        long,
+        help = "Address lookup table to compile transactions with. Create or extend one with the lut subcommand.",
+        value_parser = ValueParser::new(parse_pubkey_from_src)
+    )]
+    pub lut: Option<Pubkey>,
+}
+
+impl UseLutArgs {
+    /// Returns `None` if `--lut` was not set
+    pub async fn fetch(&self, rpc: &dyn Rpc) -> CliResult<Option<AddressLookupTableAccount>> {
+        match &self.lut {
+            Some(key) => fetch_lut(rpc, key).await.map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+pub async fn fetch_lut(rpc: &dyn Rpc, key: &Pubkey) -> CliResult<AddressLookupTableAccount> {
+    let data = rpc.get_account_data(key).await?;
+    let addresses = parse_lut_addresses(&data)
+        .ok_or_else(|| CliErr::Config(format!("{key} is not an address lookup table")))?;
+    Ok(AddressLookupTableAccount {
+        key: *key,
+        addresses,
+    })
+}
+
+fn parse_lut_addresses(data: &[u8]) -> Option<Vec<Pubkey>> {
+    if data.get(..4)? != LOOKUP_TABLE_STATE {
+        return None;
+    }
+    let addrs = data.get(LOOKUP_TABLE_META_SIZE..)?;
+    if addrs.len() % 32 != 0 {
+        return None;
+    }
+    Some(
+        addrs
+            .chunks_exact(32)
+            .map(|c| Pubkey::new_from_array(c.try_into().unwrap()))
+            .collect(),
+    )
+}
+
+/// Returns the `create_lookup_table` instruction and the address of the table it creates.
+/// `recent_slot` must be in the SlotHashes sysvar.
+pub fn create_lut_ix(
+    authority: &Pubkey,
+    payer: &Pubkey,
+    recent_slot: u64,
+) -> (Instruction, Pubkey) {
+    let (lut, bump) = Pubkey::find_program_address(
+        &[authority.as_ref(), &recent_slot.to_le_bytes()],
+        &LUT_PROGRAM_ID,
+    );
+    let mut data = vec![0, 0, 0, 0];
+    data.extend(recent_slot.to_le_bytes());
+    data.push(bump);
+    (
+        Instruction::new_with_bytes(
+            LUT_PROGRAM_ID,
+            &data,
+            vec![
+                AccountMeta::new(lut, false),
+                AccountMeta::new_readonly(*authority, true),
+                AccountMeta::new(*payer, true),
+                AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+            ],
+        ),
+        lut,
+    )
+}
+
+pub fn extend_lut_ix(
+    lut: &Pubkey,
+    authority: &Pubkey,
+    payer: &Pubkey,
+    new_addresses: &[Pubkey],
+) -> Instruction {
+    let mut data = vec![2, 0, 0, 0];
+    data.extend(u64::try_from(new_addresses.len()).unwrap().to_le_bytes());
+    new_addresses
+        .iter()
+        .for_each(|a| data.extend_from_slice(a.as_ref()));
+    Instruction::new_with_bytes(
+        LUT_PROGRAM_ID,
+        &data,
+        vec![
+            AccountMeta::new(*lut, false),
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        ],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_lut_addresses_after_meta() {
+        let addrs: [Pubkey; 3] = core::array::from_fn(|_| Pubkey::new_unique());
+        let mut data = LOOKUP_TABLE_STATE.to_vec();
+        data.resize(LOOKUP_TABLE_META_SIZE, 0);
+        addrs
+            .iter()
+            .for_each(|a| data.extend_from_slice(a.as_ref()));
+        assert_eq!(parse_lut_addresses(&data).unwrap(), addrs);
+
+        data.pop();
+        assert_eq!(parse_lut_addresses(&data), None);
+    }
+}
@@ -1,20 +1,51 @@
-use std::sync::Arc;
+use std::{process::exit, sync::Arc};
 
-use clap::{Parser, builder::ValueParser};
+use clap::{Parser, builder::ValueParser, error::ErrorKind};
 use solana_cli_config::Config;
+use solana_rpc_client::nonblocking::rpc_client::RpcClient;
 use tokio::runtime::Runtime;
 
 use crate::{
+    err::CliErr,
+    lut::UseLutArgs,
+    nonce::NonceArgs,
+    priofee::PrioFeeArgs,
+    realms::RealmsArgs,
+    sendloop::SendLoopArgs,
+    slabsrc::SlabSrcArgs,
+    squads::SquadsArgs,
     sscu::{TxSendMode, parse_solana_config_from_path},
     subcmd::Subcmd,
 };
 
 pub use inf1_pp_flatslab_core::ID as PROGRAM_ID;
 
+mod confirm;
+mod costs;
+mod err;
+mod lut;
+mod mintfilter;
+mod nonce;
+mod priofee;
+mod proposer;
+mod quote;
+mod realms;
+mod rpc;
+mod sendloop;
+mod simreport;
 mod slabcsv;
+mod slabdiff;
+mod slabsrc;
+mod snapshot;
+mod squads;
 mod sscu;
 mod subcmd;
+mod syncplan;
+#[cfg(test)]
+mod testcfg;
+mod txser;
 mod utils;
+mod verify;
 
 #[derive(Parser, Debug)]
 #[command(author, version)]
@@ -44,12 +75,56 @@ pub struct Args {
     )]
     pub fee_cb: u64,
 
+    #[command(flatten)]
+    pub prio_fee: PrioFeeArgs,
+
+    #[arg(
+        long,
+        short,
+        help = "Send transactions without prompting for confirmation. Required if stdin is not a terminal.",
+        default_value_t = false
+    )]
+    pub yes: bool,
+
+    #[command(flatten)]
+    pub squads: SquadsArgs,
+
+    #[command(flatten)]
+    pub realms: RealmsArgs,
+
+    #[command(flatten)]
+    pub nonce: NonceArgs,
+
+    #[command(flatten)]
+    pub lut: UseLutArgs,
+
+    #[command(flatten)]
+    pub send_loop: SendLoopArgs,
+
+    #[command(flatten)]
+    pub slab_src: SlabSrcArgs,
+
     #[command(subcommand)]
     pub subcmd: Subcmd,
 }
 
 fn main() {
-    let args = Args::parse();
+    let args = match Args::try_parse() {
+        Ok(args) => args,
+        Err(e) if matches!(e.kind(), ErrorKind::DisplayHelp | ErrorKind::DisplayVersion) => {
+            e.exit()
+        }
+        // clap's own exit code 2 would collide with CliErr::Config
+        Err(e) => exit_err(CliErr::Input(e.to_string().trim_end().to_owned())),
+    };
+    let rpc = RpcClient::new(args.config.json_rpc_url.to_owned());
     let rt = Runtime::new().unwrap();
-    rt.block_on(Subcmd::run(args));
+    if let Err(e) = rt.block_on(Subcmd::run(args, &rpc)) {
+        exit_err(e);
+    }
+}
+
+fn exit_err(e: CliErr) -> ! {
+    eprintln!("{e}");
+    exit(e.exit_code());
 }
@@ -0,0 +1,92 @@
+use std::{collections::HashSet, fs::read_to_string, str::FromStr, sync::Arc};
+
+use solana_pubkey::Pubkey;
+
+use crate::{
+    err::{CliErr, CliResult},
+    slabdiff::SlabDiff,
+};
+
+/// Restricts an operation to a subset of mints
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MintFilter {
+    /// If `Some`, only these mints are included
+    pub only: Option<HashSet<Pubkey>>,
+
+    /// These mints are never included
+    pub exclude: HashSet<Pubkey>,
+}
+
+impl MintFilter {
+    /// `only` and `exclude` are lists of args that are each either
+    /// a base58 mint or a path to a file of mints
+    pub fn from_args(only: &[Arc<str>], exclude: &[Arc<str>]) -> CliResult<Self> {
+        Ok(Self {
+            only: (!only.is_empty())
+                .then(|| resolve_mint_args(only))
+                .transpose()?,
+            exclude: resolve_mint_args(exclude)?,
+        })
+    }
+
+    pub fn includes(&self, mint: &Pubkey) -> bool {
+        self.only.as_ref().is_none_or(|o| o.contains(mint)) && !self.exclude.contains(mint)
+    }
+
+    pub fn apply(&self, SlabDiff { sets, removes }: &mut SlabDiff) {
+        sets.retain(|s| self.includes(&s.new.mint));
+        removes.retain(|r| self.includes(&r.mint));
+    }
+}
+
+fn resolve_mint_args(args: &[Arc<str>]) -> CliResult<HashSet<Pubkey>> {
+    args.iter().try_fold(HashSet::new(), |mut res, arg| {
+        match Pubkey::from_str(arg) {
+            Ok(mint) => {
+                res.insert(mint);
+            }
+            Err(_) => res.extend(read_mints_file(arg)?),
+        }
+        Ok(res)
+    })
+}
+
+/// One base58 mint per line. Empty lines and lines starting with `#` are ignored.
+fn read_mints_file(p: &str) -> CliResult<Vec<Pubkey>> {
+    read_to_string(p)
+        .map_err(|e| {
+            CliErr::Input(format!(
+                "{p} is neither a valid mint nor a readable file of mints: {e}"
+            ))
+        })?
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .map(|l| {
+            Pubkey::from_str(l).map_err(|e| CliErr::Input(format!("Invalid mint {l} in {p}: {e}")))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_and_exclude() {
+        let [a, b, c] = core::array::from_fn(|_| Pubkey::new_unique());
+        let filter = MintFilter::from_args(
+            &[a.to_string().into(), b.to_string().into()],
+            &[b.to_string().into()],
+        )
+        .unwrap();
+        assert!(filter.includes(&a));
+        assert!(!filter.includes(&b));
+        assert!(!filter.includes(&c));
+
+        let no_only = MintFilter::from_args(&[], &[b.to_string().into()]).unwrap();
+        assert!(no_only.includes(&a));
+        assert!(!no_only.includes(&b));
+        assert!(no_only.includes(&c));
+    }
+}
@@ -0,0 +1,187 @@
+use std::sync::Arc;
+
+use clap::{Args, builder::ValueParser};
+use solana_cli_config::Config;
+use solana_commitment_config::CommitmentConfig;
+use solana_hash::Hash;
+use solana_instruction::{AccountMeta, Instruction};
+use solana_pubkey::Pubkey;
+use solana_signer::Signer;
+use solana_transaction::versioned::VersionedTransaction;
+
+use crate::{
+    err::{CliErr, CliResult},
+    rpc::Rpc,
+    sscu::{parse_pubkey_from_src, parse_signer},
+};
+
+const SYSTEM_PROGRAM_ID: Pubkey = Pubkey::new_from_array([0u8; 32]);
+
+const RECENT_BLOCKHASHES_SYSVAR_ID: Pubkey =
+    Pubkey::from_str_const("SysvarRecentB1ockHashes11111111111111111111");
+
+/// `SystemInstruction::AdvanceNonceAccount`, bincode serialized
+const ADVANCE_NONCE_IX_DATA: [u8; 4] = [4, 0, 0, 0];
+
+/// versions: u32, state: u32, authority
+const NONCE_BLOCKHASH_OFFSET: usize = 4 + 4 + 32;
+
+/// `State::Initialized`
+const NONCE_STATE_INITIALIZED: [u8; 4] = [1, 0, 0, 0];
+
+#[derive(Args, Debug)]
+pub struct NonceArgs {
+    #[arg(
+        long,
+        help = "Durable nonce account to use in place of a recent blockhash so that transactions do not expire",
+        value_parser = ValueParser::new(parse_pubkey_from_src)
+    )]
+    pub nonce: Option<Pubkey>,
+
+    #[arg(
+        long,
+        help = "Path to nonce authority signer. Defaults to config wallet if not set.",
+        requires = "nonce"
+    )]
+    pub nonce_authority: Option<Arc<str>>,
+}
+
+impl NonceArgs {
+    /// Returns `None` if `--nonce` was not set
+    pub fn to_nonce(&self, config: &Config) -> CliResult<Option<DurableNonce>> {
+        let Self {
+            nonce,
+            nonce_authority,
+        } = self;
+        let Some(account) = *nonce else {
+            return Ok(None);
+        };
+        let authority = parse_signer(
+            nonce_authority
+                .as_deref()
+                .unwrap_or(config.keypair_path.as_str()),
+        )
+        .map_err(CliErr::Config)?;
+        Ok(Some(DurableNonce { account, authority }))
+    }
+}
+
+pub struct DurableNonce {
+    pub account: Pubkey,
+    pub authority: Box<dyn Signer>,
+}
+
+impl DurableNonce {
+    /// Must be the first instruction of the transaction
+    pub fn advance_ix(&self) -> Instruction {
+        Instruction::new_with_bytes(
+            SYSTEM_PROGRAM_ID,
+            &ADVANCE_NONCE_IX_DATA,
+            vec![
+                AccountMeta::new(self.account, false),
+                AccountMeta::new_readonly(RECENT_BLOCKHASHES_SYSVAR_ID, false),
+                AccountMeta::new_readonly(self.authority.pubkey(), true),
+            ],
+        )
+    }
+
+    /// The nonce account's current value, to be used as the transaction's blockhash.
+    /// Fetched at confirmed so that it reflects the transactions this run has confirmed.
+    pub async fn fetch_blockhash(&self, rpc: &dyn Rpc) -> CliResult<Hash> {
+        let data = rpc
+            .get_account_data_with_commitment(&self.account, CommitmentConfig::confirmed())
+            .await?
+            .value
+            .unwrap_or_default();
+        nonce_blockhash(&data).ok_or_else(|| {
+            CliErr::Config(format!(
+                "{} is not an initialized nonce account",
+                self.account
+            ))
+        })
+    }
+}
+
+/// The value of a nonce account, `None` if `data` is not that of an initialized one
+pub fn nonce_blockhash(data: &[u8]) -> Option<Hash> {
+    if data.get(4..8)? != NONCE_STATE_INITIALIZED {
+        return None;
+    }
+    let bh: [u8; 32] = data
+        .get(NONCE_BLOCKHASH_OFFSET..NONCE_BLOCKHASH_OFFSET + 32)?
+        .try_into()
+        .unwrap();
+    Some(Hash::new_from_array(bh))
+}
+
+/// The nonce account `tx` advances if it is a durable nonce transaction.
+/// `None` if the account is loaded from a lookup table.
+pub fn tx_nonce_account(tx: &VersionedTransaction) -> Option<Pubkey> {
+    let keys = tx.message.static_account_keys();
+    let ix = tx.message.instructions().first()?;
+    if *keys.get(usize::from(ix.program_id_index))? != SYSTEM_PROGRAM_ID
+        || ix.data != ADVANCE_NONCE_IX_DATA
+    {
+        return None;
+    }
+    keys.get(usize::from(*ix.accounts.first()?)).copied()
+}
+
+/// Whether `ixs` starts with an advance nonce instruction
+pub fn starts_with_advance_nonce(ixs: &[Instruction]) -> bool {
+    ixs.first()
+        .is_some_and(|ix| ix.program_id == SYSTEM_PROGRAM_ID && ix.data == ADVANCE_NONCE_IX_DATA)
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_message::{VersionedMessage, v0::Message};
+    use solana_signature::Signature;
+    use solana_signer::null_signer::NullSigner;
+
+    use super::*;
+
+    #[test]
+    fn nonce_blockhash_parse() {
+        let bh = [7u8; 32];
+        // versions::current, state::initialized, authority, blockhash, lamports_per_signature
+        let data: Vec<u8> = [1, 0, 0, 0, 1, 0, 0, 0]
+            .into_iter()
+            .chain([9u8; 32])
+            .chain(bh)
+            .chain(5000u64.to_le_bytes())
+            .collect();
+        assert_eq!(nonce_blockhash(&data), Some(Hash::new_from_array(bh)));
+
+        let mut uninit = data.clone();
+        uninit[4] = 0;
+        assert_eq!(nonce_blockhash(&uninit), None);
+    }
+
+    #[test]
+    fn tx_nonce_account_first_ix() {
+        let payer = Pubkey::new_unique();
+        let nonce = DurableNonce {
+            account: Pubkey::new_unique(),
+            authority: Box::new(NullSigner::new(&payer)),
+        };
+        let other = Instruction::new_with_bytes(Pubkey::new_unique(), &[], vec![]);
+        let tx = |ixs: &[Instruction]| {
+            let message = VersionedMessage::V0(
+                Message::try_compile(&payer, ixs, &[], Hash::default()).unwrap(),
+            );
+            VersionedTransaction {
+                signatures: vec![
+                    Signature::default();
+                    message.header().num_required_signatures.into()
+                ],
+                message,
+            }
+        };
+        assert_eq!(
+            tx_nonce_account(&tx(&[nonce.advance_ix(), other.clone()])),
+            Some(nonce.account)
+        );
+        assert_eq!(tx_nonce_account(&tx(&[other, nonce.advance_ix()])), None);
+    }
+}
@@ -0,0 +1,110 @@
+use clap::Args;
+use inf1_pp_flatslab_core::keys::SLAB_ID;
+use solana_pubkey::Pubkey;
+
+use crate::{err::CliResult, rpc::Rpc};
+
+const MICRO_LAMPORTS_PER_LAMPORT: u64 = 1_000_000;
+
+#[derive(Args, Debug)]
+pub struct PrioFeeArgs {
+    #[arg(
+        long,
+        help = "Set the compute unit price to this percentile of the slab's recent prioritization fees instead of spreading --fee-cb over the transaction",
+        value_parser = clap::value_parser!(u8).range(0..=100),
+        conflicts_with = "fee_cb"
+    )]
+    pub fee_percentile: Option<u8>,
+
+    #[arg(
+        long,
+        help = "Max ComputeBudget fees to pay per transaction with --fee-percentile, in lamports",
+        default_value_t = 1_000_000,
+        requires = "fee_percentile"
+    )]
+    pub fee_cap: u64,
+}
+
+impl PrioFeeArgs {
+    pub fn to_prio_fee(&self, fee_cb: u64) -> PrioFee {
+        let Self {
+            fee_percentile,
+            fee_cap,
+        } = self;
+        match fee_percentile {
+            Some(percentile) => PrioFee::Percentile {
+                percentile: *percentile,
+                cap: *fee_cap,
+            },
+            None => PrioFee::Total(fee_cb),
+        }
+    }
+}
+
+/// How much ComputeBudget fees to pay per transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PrioFee {
+    /// Fixed total in lamports, spread over the transaction's compute units
+    Total(u64),
+
+    /// Compute unit price from a percentile of recent prioritization fees
+    /// of the slab account, with the total capped at `cap` lamports
+    Percentile { percentile: u8, cap: u64 },
+}
+
+impl PrioFee {
+    /// Most lamports a single transaction may pay
+    pub const fn max_lamports(&self) -> u64 {
+        match self {
+            Self::Total(l) => *l,
+            Self::Percentile { cap, .. } => *cap,
+        }
+    }
+
+    /// Compute unit price, in micro-lamports, for a transaction that uses `cus` compute units
+    pub async fn cu_price(&self, rpc: &dyn Rpc, cus: u64) -> CliResult<u64> {
+        match self {
+            Self::Total(l) => Ok((l * MICRO_LAMPORTS_PER_LAMPORT).div_ceil(cus)),
+            Self::Percentile { percentile, cap } => {
+                let mut fees: Vec<_> = rpc
+                    .get_recent_prioritization_fees(&[Pubkey::new_from_array(SLAB_ID)])
+                    .await?
+                    .into_iter()
+                    .map(|f| f.prioritization_fee)
+                    .collect();
+                let price = percentile_of(&mut fees, *percentile);
+                let max_price = cap * MICRO_LAMPORTS_PER_LAMPORT / cus;
+                if price > max_price {
+                    eprintln!(
+                        "Warning: p{percentile} compute unit price {price} exceeds --fee-cap, using {max_price}"
+                    );
+                }
+                Ok(price.min(max_price))
+            }
+        }
+    }
+}
+
+/// Nearest-rank percentile of `vals`, 0 if empty
+fn percentile_of(vals: &mut [u64], percentile: u8) -> u64 {
+    if vals.is_empty() {
+        return 0;
+    }
+    vals.sort_unstable();
+    let rank = (usize::from(percentile) * vals.len()).div_ceil(100);
+    vals[rank.saturating_sub(1)]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percentile_of_nearest_rank() {
+        let mut vals = [40, 10, 50, 20, 30];
+        for (p, expected) in [(0, 10), (20, 10), (21, 20), (50, 30), (99, 50), (100, 50)] {
+            assert_eq!(percentile_of(&mut vals, p), expected, "p{p}");
+        }
+        assert_eq!(percentile_of(&mut [], 50), 0);
+    }
+}
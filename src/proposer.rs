@@ -0,0 +1,102 @@
+use solana_cli_config::Config;
+use solana_instruction::Instruction;
+use solana_pubkey::Pubkey;
+use solana_signer::{Signer, null_signer::NullSigner};
+
+use crate::{
+    err::{CliErr, CliResult},
+    realms::{Realms, RealmsArgs},
+    rpc::Rpc,
+    squads::{Squads, SquadsArgs},
+    sscu::{TxSendMode, parse_signer},
+};
+
+/// A multisig or DAO that is the slab admin,
+/// which transactions are proposed to instead of being run directly
+pub enum Proposer {
+    Squads(Squads),
+    Realms(Realms),
+}
+
+impl Proposer {
+    /// Returns `None` if `send_mode` does not create proposals
+    pub fn from_args(
+        config: &Config,
+        send_mode: TxSendMode,
+        squads: &SquadsArgs,
+        realms: &RealmsArgs,
+    ) -> CliResult<Option<Self>> {
+        Ok(match send_mode {
+            TxSendMode::Squads => Some(Self::Squads(squads.to_squads(config)?)),
+            TxSendMode::Realms => Some(Self::Realms(realms.to_realms(config)?)),
+            _ => None,
+        })
+    }
+
+    /// Signs and pays for the transactions that create proposals
+    pub fn signer(&self) -> &dyn Signer {
+        match self {
+            Self::Squads(s) => s.member.as_ref(),
+            Self::Realms(r) => r.authority.as_ref(),
+        }
+    }
+
+    /// Runs proposed instructions once approved, so should be their payer
+    pub fn executor(&self) -> Pubkey {
+        match self {
+            Self::Squads(s) => s.vault(),
+            Self::Realms(r) => r.treasury(),
+        }
+    }
+
+    /// Returns the instructions that propose `ixs`
+    pub async fn wrap_ixs(
+        &self,
+        rpc: &dyn Rpc,
+        ixs: &[Instruction],
+    ) -> CliResult<Vec<Instruction>> {
+        Ok(match self {
+            Self::Squads(s) => {
+                let tx_index = s.next_tx_index(rpc).await?;
+                eprintln!("Creating proposal {tx_index}: {}", s.proposal(tx_index));
+                s.wrap_ixs(tx_index, ixs).to_vec()
+            }
+            Self::Realms(r) => {
+                let info = r.proposal_info(rpc).await?;
+                eprintln!(
+                    "Inserting proposal transaction {}: {}",
+                    info.next_index,
+                    r.proposal_transaction(info.next_index)
+                );
+                vec![r.insert_transaction_ix(&info.token_owner_record, info.next_index, ixs)]
+            }
+        })
+    }
+
+    /// Moves on to the next proposal index once the transaction
+    /// output by [`Self::wrap_ixs`] has confirmed, so that retries reuse the same index
+    pub fn advance(&self) {
+        match self {
+            Self::Squads(s) => s.advance(),
+            Self::Realms(r) => r.advance(),
+        }
+    }
+
+    /// [`Self::wrap_ixs`] without fetching onchain state, for estimating transaction sizes
+    pub fn est_wrap_ixs(&self, ixs: &[Instruction]) -> Vec<Instruction> {
+        // indices are fixed size so their values dont matter
+        match self {
+            Self::Squads(s) => s.wrap_ixs(0, ixs).to_vec(),
+            Self::Realms(r) => vec![r.insert_transaction_ix(&Pubkey::default(), 0, ixs)],
+        }
+    }
+}
+
+/// The payer of flatslab instructions,
+/// which is the proposer's executor if `proposer` is set, else the config wallet
+pub fn parse_payer(config: &Config, proposer: Option<&Proposer>) -> CliResult<Box<dyn Signer>> {
+    match proposer {
+        Some(p) => Ok(Box::new(NullSigner::new(&p.executor()))),
+        None => parse_signer(&config.keypair_path).map_err(CliErr::Config),
+    }
+}
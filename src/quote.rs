@@ -0,0 +1,219 @@
+use std::fmt;
+
+use inf1_pp_core::{
+    instructions::price::{exact_in::PriceExactInIxArgs, exact_out::PriceExactOutIxArgs},
+    traits::main::{PriceExactIn, PriceExactOut},
+};
+use inf1_pp_flatslab_core::{accounts::Slab, pricing::FlatSlabSwapPricing};
+use solana_pubkey::Pubkey;
+
+use crate::{
+    err::{CliErr, CliResult},
+    slabcsv::SlabCsvEntry,
+    slabdiff::{NANOS_PER_BPS, NANOS_PER_PCT},
+};
+
+/// Whether [`QuoteAmt::amt`] is the amount going in or coming out of the swap
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum QuoteDir {
+    ExactIn,
+    ExactOut,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct QuoteAmt {
+    pub dir: QuoteDir,
+
+    /// SOL value given by the user
+    pub amt: u64,
+
+    /// SOL value on the other side of the swap after fees
+    pub quoted: u64,
+}
+
+/// The fee charged for swapping `inp.mint` for `out.mint`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Quote {
+    pub inp: SlabCsvEntry,
+    pub out: SlabCsvEntry,
+    pub amt: Option<QuoteAmt>,
+}
+
+impl Quote {
+    /// Errs if either mint is not on the slab, if they are the same mint
+    /// or if the swap cannot be priced
+    pub fn new(
+        slab: &Slab,
+        inp: &Pubkey,
+        out: &Pubkey,
+        amt: Option<(QuoteDir, u64)>,
+    ) -> CliResult<Self> {
+        if inp == out {
+            return Err(CliErr::Input(format!(
+                "Cannot quote a swap of {inp} for itself"
+            )));
+        }
+        let entries = slab.entries();
+        let [inp, out] = [inp, out].map(|mint| {
+            entries
+                .find_by_mint(mint.as_array())
+                .map(|e| SlabCsvEntry::from(*e))
+                .map_err(|_| CliErr::Input(format!("Mint {mint} is not on the slab")))
+        });
+        let (inp, out) = (inp?, out?);
+
+        let amt = amt
+            .map(|(dir, amt)| {
+                let pricing = FlatSlabSwapPricing {
+                    inp_fee_nanos: inp.inp,
+                    out_fee_nanos: out.out,
+                };
+                match dir {
+                    QuoteDir::ExactIn => pricing.price_exact_in(PriceExactInIxArgs {
+                        amt,
+                        sol_value: amt,
+                    }),
+                    QuoteDir::ExactOut => pricing.price_exact_out(PriceExactOutIxArgs {
+                        amt,
+                        sol_value: amt,
+                    }),
+                }
+                .map(|quoted| QuoteAmt { dir, amt, quoted })
+                .map_err(|e| CliErr::Input(format!("Failed to price swap: {e:?}")))
+            })
+            .transpose()?;
+
+        Ok(Self { inp, out, amt })
+    }
+
+    /// Combined fee of the swap. i64 to avoid overflow.
+    pub fn fee_nanos(&self) -> i64 {
+        i64::from(self.inp.inp) + i64::from(self.out.out)
+    }
+}
+
+impl fmt::Display for Quote {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self { inp, out, amt } = self;
+        writeln!(f, "inp: {} (inp fee {} nanos)", inp.mint, inp.inp)?;
+        writeln!(f, "out: {} (out fee {} nanos)", out.mint, out.out)?;
+        let fee_nanos = self.fee_nanos();
+        let fee = fee_nanos as f64;
+        writeln!(
+            f,
+            "fee: {fee_nanos} nanos, {:.2} bps ({:.4}%)",
+            fee / NANOS_PER_BPS,
+            fee / NANOS_PER_PCT
+        )?;
+        if let Some(QuoteAmt { dir, amt, quoted }) = amt {
+            let (inp_amt, out_amt) = match dir {
+                QuoteDir::ExactIn => (amt, quoted),
+                QuoteDir::ExactOut => (quoted, amt),
+            };
+            writeln!(f, "inp SOL value: {inp_amt}")?;
+            writeln!(f, "out SOL value: {out_amt}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::rpc::mock::mock_slab_data;
+
+    use super::*;
+
+    /// 1% combined fee on 1 SOL of value
+    const AMT: u64 = 1_000_000_000;
+
+    fn quote(inp: &Pubkey, out: &Pubkey, amt: Option<(QuoteDir, u64)>) -> CliResult<Quote> {
+        let [m0, m1] = mints();
+        let slab_d = mock_slab_data(
+            &Pubkey::new_unique(),
+            &[
+                SlabCsvEntry {
+                    mint: m0,
+                    inp: 4_000_000,
+                    out: 0,
+                },
+                SlabCsvEntry {
+                    mint: m1,
+                    inp: 0,
+                    out: 6_000_000,
+                },
+            ],
+        );
+        Quote::new(&Slab::of_acc_data(&slab_d).unwrap(), inp, out, amt)
+    }
+
+    fn mints() -> [Pubkey; 2] {
+        [[1; 32], [2; 32]].map(Pubkey::new_from_array)
+    }
+
+    #[test]
+    fn quote_amounts() {
+        let [m0, m1] = mints();
+
+        let q = quote(&m0, &m1, None).unwrap();
+        assert_eq!((q.inp.mint, q.out.mint), (m0, m1));
+        assert_eq!(q.fee_nanos(), 10_000_000);
+        assert_eq!(q.amt, None);
+
+        let QuoteAmt { dir, amt, quoted } = quote(&m0, &m1, Some((QuoteDir::ExactIn, AMT)))
+            .unwrap()
+            .amt
+            .unwrap();
+        assert_eq!((dir, amt), (QuoteDir::ExactIn, AMT));
+        // 99% of AMT, allowing for rounding
+        assert!(quoted.abs_diff(990_000_000) <= 1, "{quoted}");
+
+        let QuoteAmt { dir, amt, quoted } = quote(&m0, &m1, Some((QuoteDir::ExactOut, AMT)))
+            .unwrap()
+            .amt
+            .unwrap();
+        assert_eq!((dir, amt), (QuoteDir::ExactOut, AMT));
+        // AMT / 99%, allowing for rounding
+        assert!(quoted.abs_diff(1_010_101_010) <= 1, "{quoted}");
+    }
+
+    #[test]
+    fn quote_missing_mint() {
+        let [m0, _] = mints();
+        let missing = Pubkey::new_unique();
+        for (inp, out) in [(&m0, &missing), (&missing, &m0)] {
+            match quote(inp, out, None) {
+                Err(CliErr::Input(msg)) => {
+                    assert_eq!(msg, format!("Mint {missing} is not on the slab"))
+                }
+                res => panic!("{res:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn quote_same_mint() {
+        let [m0, _] = mints();
+        assert!(matches!(
+            quote(&m0, &m0, Some((QuoteDir::ExactIn, AMT))),
+            Err(CliErr::Input(_))
+        ));
+    }
+
+    #[test]
+    fn fee_nanos_no_overflow() {
+        let q = Quote {
+            inp: SlabCsvEntry {
+                mint: Pubkey::new_unique(),
+                inp: i32::MAX,
+                out: 0,
+            },
+            out: SlabCsvEntry {
+                mint: Pubkey::new_unique(),
+                inp: 0,
+                out: i32::MAX,
+            },
+            amt: None,
+        };
+        assert_eq!(q.fee_nanos(), 2 * i64::from(i32::MAX));
+    }
+}
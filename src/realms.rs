@@ -0,0 +1,374 @@
+//! Minimal SPL Governance v3 client for inserting transactions into proposals.
+//! Hand-rolled to avoid pulling in spl-governance's solana-1.X deps.
+
+use std::sync::Mutex;
+
+use clap::{Args, builder::ValueParser};
+use solana_cli_config::Config;
+use solana_commitment_config::CommitmentConfig;
+use solana_instruction::{AccountMeta, Instruction};
+use solana_pubkey::Pubkey;
+use solana_signer::Signer;
+
+use crate::{
+    err::{CliErr, CliResult},
+    rpc::Rpc,
+    sscu::{parse_pubkey_from_src, parse_signer},
+};
+
+pub const DEFAULT_GOVERNANCE_PROGRAM_ID: Pubkey =
+    Pubkey::from_str_const("GovER5Lthms3bLBqWub97yVrMmEogzX7xNjdXpPPCVZw");
+
+const SYSTEM_PROGRAM_ID: Pubkey = Pubkey::new_from_array([0u8; 32]);
+
+const RENT_SYSVAR_ID: Pubkey =
+    Pubkey::from_str_const("SysvarRent111111111111111111111111111111111");
+
+/// `GovernanceInstruction::InsertTransaction` borsh enum index
+const INSERT_TRANSACTION_IX_DISCM: u8 = 9;
+
+/// `GovernanceAccountType::ProposalV2`
+const PROPOSAL_V2_ACC_TYPE: u8 = 14;
+
+#[derive(Args, Debug)]
+pub struct RealmsArgs {
+    #[arg(
+        long,
+        help = "SPL Governance governance account whose native treasury is the slab admin. Required for --send-mode realms.",
+        value_parser = ValueParser::new(parse_pubkey_from_src)
+    )]
+    pub realms_governance: Option<Pubkey>,
+
+    #[arg(
+        long,
+        help = "SPL Governance proposal to insert transactions into. Required for --send-mode realms.",
+        value_parser = ValueParser::new(parse_pubkey_from_src)
+    )]
+    pub realms_proposal: Option<Pubkey>,
+
+    #[arg(
+        long,
+        help = "Index of the proposal option to insert transactions into",
+        default_value_t = 0
+    )]
+    pub realms_option_index: u8,
+
+    #[arg(
+        long,
+        help = "Seconds after the proposal succeeds before its transactions can be executed. Must be at least the governance's min_transaction_hold_up_time.",
+        default_value_t = 0
+    )]
+    pub realms_hold_up_time: u32,
+
+    #[arg(
+        long,
+        help = "SPL Governance program the proposal belongs to",
+        default_value_t = DEFAULT_GOVERNANCE_PROGRAM_ID,
+        value_parser = ValueParser::new(parse_pubkey_from_src)
+    )]
+    pub realms_program: Pubkey,
+}
+
+pub struct Realms {
+    pub program_id: Pubkey,
+    pub governance: Pubkey,
+    pub proposal: Pubkey,
+    pub option_index: u8,
+    pub hold_up_time: u32,
+
+    /// Governance authority of the proposal's owner, and payer
+    pub authority: Box<dyn Signer>,
+
+    /// Set once fetched, then tracked by [`Self::advance`]
+    /// since the RPC may lag behind the transactions this run has inserted
+    pub proposal_info: Mutex<Option<ProposalInfo>>,
+}
+
+impl RealmsArgs {
+    /// Governance authority is the config wallet
+    pub fn to_realms(&self, config: &Config) -> CliResult<Realms> {
+        let Self {
+            realms_governance,
+            realms_proposal,
+            realms_option_index,
+            realms_hold_up_time,
+            realms_program,
+        } = self;
+        let required = |pk: &Option<Pubkey>, flag: &str| {
+            pk.ok_or_else(|| CliErr::Config(format!("{flag} is required for --send-mode realms")))
+        };
+        let governance = required(realms_governance, "--realms-governance")?;
+        let proposal = required(realms_proposal, "--realms-proposal")?;
+        Ok(Realms {
+            program_id: *realms_program,
+            governance,
+            proposal,
+            option_index: *realms_option_index,
+            hold_up_time: *realms_hold_up_time,
+            authority: parse_signer(&config.keypair_path).map_err(CliErr::Config)?,
+            proposal_info: Mutex::new(None),
+        })
+    }
+}
+
+/// The fields of a `ProposalV2` account needed to insert transactions into it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ProposalInfo {
+    pub governance: Pubkey,
+    pub token_owner_record: Pubkey,
+
+    /// `transactions_next_index` of the option transactions are inserted into
+    pub next_index: u16,
+}
+
+impl Realms {
+    pub async fn proposal_info(&self, rpc: &dyn Rpc) -> CliResult<ProposalInfo> {
+        if let Some(info) = *self.proposal_info.lock().unwrap() {
+            return Ok(info);
+        }
+        let data = rpc
+            .get_account_data_with_commitment(&self.proposal, CommitmentConfig::confirmed())
+            .await?
+            .value
+            .unwrap_or_default();
+        let info = parse_proposal_info(&data, self.option_index).ok_or_else(|| {
+            CliErr::Config(format!(
+                "{} is not a ProposalV2 with option {}",
+                self.proposal, self.option_index
+            ))
+        })?;
+        if info.governance != self.governance {
+            return Err(CliErr::Config(format!(
+                "Proposal {} belongs to governance {}, not {}",
+                self.proposal, info.governance, self.governance
+            )));
+        }
+        *self.proposal_info.lock().unwrap() = Some(info);
+        Ok(info)
+    }
+
+    /// Marks the proposal transaction at [`ProposalInfo::next_index`] as inserted.
+    /// Must only be called once the transaction inserting it has confirmed.
+    pub fn advance(&self) {
+        if let Some(info) = self.proposal_info.lock().unwrap().as_mut() {
+            info.next_index += 1;
+        }
+    }
+
+    /// The governance's native treasury, which runs and pays for proposed instructions
+    pub fn treasury(&self) -> Pubkey {
+        Pubkey::find_program_address(
+            &[b"native-treasury", self.governance.as_ref()],
+            &self.program_id,
+        )
+        .0
+    }
+
+    pub fn proposal_transaction(&self, index: u16) -> Pubkey {
+        Pubkey::find_program_address(
+            &[
+                b"governance",
+                self.proposal.as_ref(),
+                &[self.option_index],
+                &index.to_le_bytes(),
+            ],
+            &self.program_id,
+        )
+        .0
+    }
+
+    /// Returns the `insert_transaction` instruction that inserts `ixs`
+    /// as a single proposal transaction at `index`
+    pub fn insert_transaction_ix(
+        &self,
+        token_owner_record: &Pubkey,
+        index: u16,
+        ixs: &[Instruction],
+    ) -> Instruction {
+        let authority = self.authority.pubkey();
+
+        let mut data = vec![INSERT_TRANSACTION_IX_DISCM, self.option_index];
+        data.extend(index.to_le_bytes());
+        data.extend(self.hold_up_time.to_le_bytes());
+        data.extend(u32::try_from(ixs.len()).unwrap().to_le_bytes());
+        ixs.iter().for_each(|ix| {
+            data.extend_from_slice(ix.program_id.as_ref());
+            data.extend(u32::try_from(ix.accounts.len()).unwrap().to_le_bytes());
+            ix.accounts.iter().for_each(|a| {
+                data.extend_from_slice(a.pubkey.as_ref());
+                data.push(a.is_signer.into());
+                data.push(a.is_writable.into());
+            });
+            data.extend(u32::try_from(ix.data.len()).unwrap().to_le_bytes());
+            data.extend_from_slice(&ix.data);
+        });
+
+        Instruction::new_with_bytes(
+            self.program_id,
+            &data,
+            vec![
+                AccountMeta::new_readonly(self.governance, false),
+                AccountMeta::new(self.proposal, false),
+                AccountMeta::new_readonly(*token_owner_record, false),
+                AccountMeta::new_readonly(authority, true),
+                AccountMeta::new(self.proposal_transaction(index), false),
+                AccountMeta::new(authority, true),
+                AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+                AccountMeta::new_readonly(RENT_SYSVAR_ID, false),
+            ],
+        )
+    }
+}
+
+fn parse_proposal_info(data: &[u8], option_index: u8) -> Option<ProposalInfo> {
+    let mut r = Reader(data);
+    if r.u8()? != PROPOSAL_V2_ACC_TYPE {
+        return None;
+    }
+    let governance = r.pubkey()?;
+    let _governing_token_mint = r.pubkey()?;
+    let _state = r.u8()?;
+    let token_owner_record = r.pubkey()?;
+    let _signatories_count = r.u8()?;
+    let _signatories_signed_off_count = r.u8()?;
+    // vote_type: SingleChoice | MultiChoice { choice_type, min_voter_options, max_voter_options, max_winning_options }
+    if r.u8()? == 1 {
+        r.take(4)?;
+    }
+    let n_options = r.u32()?;
+    for i in 0..n_options {
+        let label_len = r.u32()?;
+        r.take(label_len.try_into().ok()?)?;
+        // vote_weight, vote_result, transactions_executed_count, transactions_count
+        r.take(8 + 1 + 2 + 2)?;
+        let next_index = r.u16()?;
+        if i == u32::from(option_index) {
+            return Some(ProposalInfo {
+                governance,
+                token_owner_record,
+                next_index,
+            });
+        }
+    }
+    None
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Option<&'a [u8]> {
+        if self.0.len() < n {
+            return None;
+        }
+        let (res, rem) = self.0.split_at(n);
+        self.0 = rem;
+        Some(res)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        self.take(1).map(|b| b[0])
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        self.take(2)
+            .map(|b| u16::from_le_bytes(b.try_into().unwrap()))
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        self.take(4)
+            .map(|b| u32::from_le_bytes(b.try_into().unwrap()))
+    }
+
+    fn pubkey(&mut self) -> Option<Pubkey> {
+        self.take(32)
+            .map(|b| Pubkey::new_from_array(b.try_into().unwrap()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_keypair::Keypair;
+    use solana_signer::null_signer::NullSigner;
+
+    use crate::{
+        proposer::Proposer, rpc::mock::MockRpc, sscu::TxSendMode, testcfg::test_ctx,
+        txser::decompile_ixs, utils::TxCtx,
+    };
+
+    use super::*;
+
+    fn proposal_data(governance: &Pubkey, tor: &Pubkey, options: &[(&str, u16)]) -> Vec<u8> {
+        let mut data = vec![PROPOSAL_V2_ACC_TYPE];
+        data.extend(governance.to_bytes());
+        data.extend(Pubkey::new_unique().to_bytes());
+        data.push(0);
+        data.extend(tor.to_bytes());
+        data.extend([1, 0]);
+        data.extend([1, 0, 1, 2, 1]);
+        data.extend(u32::try_from(options.len()).unwrap().to_le_bytes());
+        options.iter().for_each(|(label, next_index)| {
+            data.extend(u32::try_from(label.len()).unwrap().to_le_bytes());
+            data.extend(label.as_bytes());
+            data.extend([0u8; 8 + 1 + 2 + 2]);
+            data.extend(next_index.to_le_bytes());
+        });
+        // rest of the account that is not parsed
+        data.extend([0u8; 64]);
+        data
+    }
+
+    #[test]
+    fn parse_proposal_info_multi_choice() {
+        let [governance, tor] = core::array::from_fn(|_| Pubkey::new_unique());
+        let data = proposal_data(&governance, &tor, &[("yes", 3), ("also yes", 7)]);
+
+        assert_eq!(
+            parse_proposal_info(&data, 1),
+            Some(ProposalInfo {
+                governance,
+                token_owner_record: tor,
+                next_index: 7,
+            })
+        );
+        assert_eq!(parse_proposal_info(&data, 2), None);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn inserts_consecutive_indices() {
+        let [governance, proposal, tor] = core::array::from_fn(|_| Pubkey::new_unique());
+        // never updated, like a lagging rpc
+        let rpc = MockRpc::default()
+            .with_account(proposal, proposal_data(&governance, &tor, &[("yes", 3)]));
+        let proposer = Proposer::Realms(Realms {
+            program_id: DEFAULT_GOVERNANCE_PROGRAM_ID,
+            governance,
+            proposal,
+            option_index: 0,
+            hold_up_time: 0,
+            authority: Box::new(Keypair::new()),
+            proposal_info: Mutex::new(None),
+        });
+        let ctx = TxCtx {
+            proposer: Some(&proposer),
+            ..test_ctx(&rpc, TxSendMode::Realms)
+        };
+        let treasury = NullSigner::new(&proposer.executor());
+        for _ in 0..2 {
+            let ix = Instruction::new_with_bytes(Pubkey::new_unique(), &[], vec![]);
+            ctx.send(vec![ix], vec![&treasury]).await.unwrap();
+        }
+
+        let indices: Vec<_> = rpc
+            .sent()
+            .iter()
+            .map(|tx| {
+                let ix = decompile_ixs(&tx.message)
+                    .into_iter()
+                    .find(|ix| ix.program_id == DEFAULT_GOVERNANCE_PROGRAM_ID)
+                    .unwrap();
+                u16::from_le_bytes(ix.data[2..4].try_into().unwrap())
+            })
+            .collect();
+        assert_eq!(indices, [3, 4]);
+    }
+}
@@ -0,0 +1,290 @@
+use std::{collections::HashMap, sync::Mutex};
+
+use async_trait::async_trait;
+use inf1_pp_flatslab_core::{accounts::Slab, keys::SLAB_ID};
+use solana_commitment_config::CommitmentConfig;
+use solana_hash::Hash;
+use solana_pubkey::Pubkey;
+use solana_rpc_client_api::client_error::{ErrorKind as ClientErrorKind, Result as ClientResult};
+use solana_rpc_client_types::{
+    config::{RpcSendTransactionConfig, RpcSimulateTransactionConfig},
+    response::{Response, RpcPrioritizationFee, RpcResponseContext, RpcSimulateTransactionResult},
+};
+use solana_signature::Signature;
+use solana_transaction::versioned::VersionedTransaction;
+use solana_transaction_error::TransactionError;
+use solana_transaction_status_client_types::{TransactionConfirmationStatus, TransactionStatus};
+
+use crate::{
+    rpc::{Rpc, RpcResult},
+    slabcsv::SlabCsvEntry,
+    squads::{MULTISIG_ACC_DISCM, SQUADS_PROGRAM_ID},
+};
+
+/// Compute units every simulated transaction consumes
+pub const MOCK_UNITS_CONSUMED: u64 = 10_000;
+
+/// Account data of a slab with `admin` and `entries`
+pub fn mock_slab_data(admin: &Pubkey, entries: &[SlabCsvEntry]) -> Vec<u8> {
+    // the smallest data that parses is that of an empty slab
+    let header_len = (0..=1024)
+        .find(|n| Slab::of_acc_data(&vec![0u8; *n]).is_some())
+        .unwrap();
+    let mut entries = entries.to_vec();
+    entries.sort_unstable_by_key(|e| e.mint);
+
+    let mut res = vec![0u8; header_len];
+    res[..32].copy_from_slice(admin.as_ref());
+    entries.iter().for_each(|e| {
+        res.extend(e.mint.to_bytes());
+        res.extend(e.inp.to_le_bytes());
+        res.extend(e.out.to_le_bytes());
+    });
+
+    // layout above is assumed, check it against the program's
+    let slab = Slab::of_acc_data(&res).unwrap();
+    assert_eq!(slab.admin(), admin.as_array());
+    assert_eq!(
+        slab.entries()
+            .0
+            .iter()
+            .map(|e| SlabCsvEntry::from(*e))
+            .collect::<Vec<_>>(),
+        entries
+    );
+    res
+}
+
+/// Address and account data of a multisig with `members` that have every permission
+pub fn mock_multisig(create_key: &Pubkey, members: &[Pubkey], tx_index: u64) -> (Pubkey, Vec<u8>) {
+    let (multisig, bump) = Pubkey::find_program_address(
+        &[b"multisig", b"multisig", create_key.as_ref()],
+        &SQUADS_PROGRAM_ID,
+    );
+    let mut data = MULTISIG_ACC_DISCM.to_vec();
+    data.extend(create_key.to_bytes());
+    data.extend([0u8; 32]); // config_authority: None
+    data.extend(1u16.to_le_bytes()); // threshold
+    data.extend(0u32.to_le_bytes()); // time_lock
+    data.extend(tx_index.to_le_bytes());
+    data.extend(0u64.to_le_bytes()); // stale_transaction_index
+    data.push(0); // rent_collector: None
+    data.push(bump);
+    data.extend(u32::try_from(members.len()).unwrap().to_le_bytes());
+    members.iter().for_each(|m| {
+        data.extend(m.to_bytes());
+        data.push(0b111); // initiate, vote, execute
+    });
+    (multisig, data)
+}
+
+/// Status of a transaction that has reached `confirmation_status`
+pub fn mock_status(
+    confirmation_status: TransactionConfirmationStatus,
+    err: Option<TransactionError>,
+) -> TransactionStatus {
+    TransactionStatus {
+        slot: 0,
+        confirmations: match confirmation_status {
+            TransactionConfirmationStatus::Finalized => None,
+            _ => Some(0),
+        },
+        status: err.clone().map_or(Ok(()), Err),
+        err,
+        confirmation_status: Some(confirmation_status),
+    }
+}
+
+/// In-memory [`Rpc`] backed by a fixed set of accounts.
+///
+/// Simulations always succeed and sent transactions are recorded and confirmed immediately
+/// without being executed.
+#[derive(Debug, Default)]
+pub struct MockRpc {
+    pub slot: u64,
+
+    /// Latest blockhash before any transaction is sent.
+    /// Every distinct transaction sent moves it on so that earlier ones expire.
+    pub blockhash: Hash,
+    pub accounts: HashMap<Pubkey, Vec<u8>>,
+
+    /// Balance of every account not in `balances`
+    pub balance: u64,
+    pub balances: HashMap<Pubkey, u64>,
+
+    /// Statuses of transactions that were not sent through this mock, e.g. by a previous run
+    pub statuses: HashMap<Signature, TransactionStatus>,
+
+    /// Number of the first distinct transactions sent that never land
+    pub n_dropped: usize,
+
+    /// If set, statuses are only returned by [`Rpc::get_signature_statuses_with_history`],
+    /// as if the transactions had already left the status cache
+    pub history_only: bool,
+
+    /// Distinct transactions sent, in order, including dropped ones
+    pub sent: Mutex<Vec<VersionedTransaction>>,
+}
+
+impl MockRpc {
+    pub fn with_account(mut self, key: Pubkey, data: Vec<u8>) -> Self {
+        self.accounts.insert(key, data);
+        self
+    }
+
+    pub fn with_balance(mut self, key: Pubkey, balance: u64) -> Self {
+        self.balances.insert(key, balance);
+        self
+    }
+
+    pub fn with_status(mut self, sig: Signature, status: TransactionStatus) -> Self {
+        self.statuses.insert(sig, status);
+        self
+    }
+
+    pub fn with_slab(self, admin: &Pubkey, entries: &[SlabCsvEntry]) -> Self {
+        self.with_account(SLAB_ID.into(), mock_slab_data(admin, entries))
+    }
+
+    pub fn sent(&self) -> Vec<VersionedTransaction> {
+        self.sent.lock().unwrap().clone()
+    }
+
+    fn ctx<T>(&self, value: T) -> Response<T> {
+        Response {
+            context: RpcResponseContext {
+                slot: self.slot,
+                api_version: None,
+            },
+            value,
+        }
+    }
+
+    fn latest_blockhash(&self) -> Hash {
+        let n_sent = self.sent.lock().unwrap().len();
+        let mut res = self.blockhash.to_bytes();
+        res.iter_mut()
+            .zip(n_sent.to_le_bytes())
+            .for_each(|(b, n)| *b ^= n);
+        Hash::new_from_array(res)
+    }
+
+    fn statuses(&self, sigs: &[Signature]) -> Vec<Option<TransactionStatus>> {
+        let sent = self.sent.lock().unwrap();
+        sigs.iter()
+            .map(|sig| {
+                self.statuses.get(sig).cloned().or_else(|| {
+                    sent.iter()
+                        .skip(self.n_dropped)
+                        .any(|tx| tx.signatures[0] == *sig)
+                        .then(|| mock_status(TransactionConfirmationStatus::Finalized, None))
+                })
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl Rpc for MockRpc {
+    fn url(&self) -> String {
+        "mock".to_owned()
+    }
+
+    fn commitment(&self) -> CommitmentConfig {
+        CommitmentConfig::confirmed()
+    }
+
+    async fn get_account_data(&self, key: &Pubkey) -> ClientResult<Vec<u8>> {
+        self.accounts
+            .get(key)
+            .cloned()
+            .ok_or_else(|| ClientErrorKind::Custom(format!("AccountNotFound: pubkey={key}")).into())
+    }
+
+    async fn get_account_data_with_commitment(
+        &self,
+        key: &Pubkey,
+        _commitment: CommitmentConfig,
+    ) -> RpcResult<Option<Vec<u8>>> {
+        Ok(self.ctx(self.accounts.get(key).cloned()))
+    }
+
+    async fn get_balance(&self, key: &Pubkey) -> ClientResult<u64> {
+        Ok(self.balances.get(key).copied().unwrap_or(self.balance))
+    }
+
+    async fn get_minimum_balance_for_rent_exemption(&self, len: usize) -> ClientResult<u64> {
+        // same formula as the default rent sysvar
+        Ok((128 + len as u64) * 6_960)
+    }
+
+    async fn get_slot_with_commitment(&self, _commitment: CommitmentConfig) -> ClientResult<u64> {
+        Ok(self.slot)
+    }
+
+    async fn get_latest_blockhash(&self) -> ClientResult<Hash> {
+        Ok(self.latest_blockhash())
+    }
+
+    async fn is_blockhash_valid(
+        &self,
+        blockhash: &Hash,
+        _commitment: CommitmentConfig,
+    ) -> ClientResult<bool> {
+        Ok(*blockhash == self.latest_blockhash())
+    }
+
+    async fn get_recent_prioritization_fees(
+        &self,
+        _keys: &[Pubkey],
+    ) -> ClientResult<Vec<RpcPrioritizationFee>> {
+        Ok(vec![])
+    }
+
+    async fn simulate_transaction_with_config(
+        &self,
+        _tx: &VersionedTransaction,
+        _config: RpcSimulateTransactionConfig,
+    ) -> RpcResult<RpcSimulateTransactionResult> {
+        // missing optional fields default to None
+        let result = serde_json::from_value(serde_json::json!({
+            "err": null,
+            "logs": [],
+            "accounts": null,
+            "unitsConsumed": MOCK_UNITS_CONSUMED,
+            "returnData": null,
+        }))
+        .unwrap();
+        Ok(self.ctx(result))
+    }
+
+    async fn send_transaction_with_config(
+        &self,
+        tx: &VersionedTransaction,
+        _config: RpcSendTransactionConfig,
+    ) -> ClientResult<Signature> {
+        let mut sent = self.sent.lock().unwrap();
+        // rebroadcasts
+        if !sent.iter().any(|t| t.signatures[0] == tx.signatures[0]) {
+            sent.push(tx.clone());
+        }
+        Ok(tx.signatures[0])
+    }
+
+    async fn get_signature_statuses(
+        &self,
+        sigs: &[Signature],
+    ) -> RpcResult<Vec<Option<TransactionStatus>>> {
+        if self.history_only {
+            return Ok(self.ctx(vec![None; sigs.len()]));
+        }
+        Ok(self.ctx(self.statuses(sigs)))
+    }
+
+    async fn get_signature_statuses_with_history(
+        &self,
+        sigs: &[Signature],
+    ) -> RpcResult<Vec<Option<TransactionStatus>>> {
+        Ok(self.ctx(self.statuses(sigs)))
+    }
+}
@@ -0,0 +1,172 @@
+//! The RPC operations the CLI needs, so that commands can run against
+//! backends other than a live cluster.
+
+use async_trait::async_trait;
+use solana_commitment_config::CommitmentConfig;
+use solana_hash::Hash;
+use solana_pubkey::Pubkey;
+use solana_rpc_client::nonblocking::rpc_client::RpcClient;
+use solana_rpc_client_api::client_error::Result as ClientResult;
+use solana_rpc_client_types::{
+    config::{RpcSendTransactionConfig, RpcSimulateTransactionConfig},
+    response::{Response, RpcPrioritizationFee, RpcSimulateTransactionResult},
+};
+use solana_signature::Signature;
+use solana_transaction::versioned::VersionedTransaction;
+use solana_transaction_status_client_types::TransactionStatus;
+
+#[cfg(test)]
+pub mod mock;
+
+#[cfg(all(test, feature = "e2e"))]
+pub mod svm;
+
+pub type RpcResult<T> = ClientResult<Response<T>>;
+
+/// Method names and semantics follow [`RpcClient`]'s
+#[async_trait]
+pub trait Rpc: Send + Sync {
+    /// Displayed to the user, e.g. the cluster's url
+    fn url(&self) -> String;
+
+    fn commitment(&self) -> CommitmentConfig;
+
+    async fn get_account_data(&self, key: &Pubkey) -> ClientResult<Vec<u8>>;
+
+    /// `None` if the account does not exist
+    async fn get_account_data_with_commitment(
+        &self,
+        key: &Pubkey,
+        commitment: CommitmentConfig,
+    ) -> RpcResult<Option<Vec<u8>>>;
+
+    async fn get_balance(&self, key: &Pubkey) -> ClientResult<u64>;
+
+    async fn get_minimum_balance_for_rent_exemption(&self, len: usize) -> ClientResult<u64>;
+
+    async fn get_slot_with_commitment(&self, commitment: CommitmentConfig) -> ClientResult<u64>;
+
+    async fn get_latest_blockhash(&self) -> ClientResult<Hash>;
+
+    async fn is_blockhash_valid(
+        &self,
+        blockhash: &Hash,
+        commitment: CommitmentConfig,
+    ) -> ClientResult<bool>;
+
+    async fn get_recent_prioritization_fees(
+        &self,
+        keys: &[Pubkey],
+    ) -> ClientResult<Vec<RpcPrioritizationFee>>;
+
+    async fn simulate_transaction_with_config(
+        &self,
+        tx: &VersionedTransaction,
+        config: RpcSimulateTransactionConfig,
+    ) -> RpcResult<RpcSimulateTransactionResult>;
+
+    async fn send_transaction_with_config(
+        &self,
+        tx: &VersionedTransaction,
+        config: RpcSendTransactionConfig,
+    ) -> ClientResult<Signature>;
+
+    async fn get_signature_statuses(
+        &self,
+        sigs: &[Signature],
+    ) -> RpcResult<Vec<Option<TransactionStatus>>>;
+
+    async fn get_signature_statuses_with_history(
+        &self,
+        sigs: &[Signature],
+    ) -> RpcResult<Vec<Option<TransactionStatus>>>;
+}
+
+#[async_trait]
+impl Rpc for RpcClient {
+    fn url(&self) -> String {
+        RpcClient::url(self)
+    }
+
+    fn commitment(&self) -> CommitmentConfig {
+        RpcClient::commitment(self)
+    }
+
+    async fn get_account_data(&self, key: &Pubkey) -> ClientResult<Vec<u8>> {
+        RpcClient::get_account_data(self, key).await
+    }
+
+    async fn get_account_data_with_commitment(
+        &self,
+        key: &Pubkey,
+        commitment: CommitmentConfig,
+    ) -> RpcResult<Option<Vec<u8>>> {
+        let Response { context, value } =
+            RpcClient::get_account_with_commitment(self, key, commitment).await?;
+        Ok(Response {
+            context,
+            value: value.map(|acc| acc.data),
+        })
+    }
+
+    async fn get_balance(&self, key: &Pubkey) -> ClientResult<u64> {
+        RpcClient::get_balance(self, key).await
+    }
+
+    async fn get_minimum_balance_for_rent_exemption(&self, len: usize) -> ClientResult<u64> {
+        RpcClient::get_minimum_balance_for_rent_exemption(self, len).await
+    }
+
+    async fn get_slot_with_commitment(&self, commitment: CommitmentConfig) -> ClientResult<u64> {
+        RpcClient::get_slot_with_commitment(self, commitment).await
+    }
+
+    async fn get_latest_blockhash(&self) -> ClientResult<Hash> {
+        RpcClient::get_latest_blockhash(self).await
+    }
+
+    async fn is_blockhash_valid(
+        &self,
+        blockhash: &Hash,
+        commitment: CommitmentConfig,
+    ) -> ClientResult<bool> {
+        RpcClient::is_blockhash_valid(self, blockhash, commitment).await
+    }
+
+    async fn get_recent_prioritization_fees(
+        &self,
+        keys: &[Pubkey],
+    ) -> ClientResult<Vec<RpcPrioritizationFee>> {
+        RpcClient::get_recent_prioritization_fees(self, keys).await
+    }
+
+    async fn simulate_transaction_with_config(
+        &self,
+        tx: &VersionedTransaction,
+        config: RpcSimulateTransactionConfig,
+    ) -> RpcResult<RpcSimulateTransactionResult> {
+        RpcClient::simulate_transaction_with_config(self, tx, config).await
+    }
+
+    async fn send_transaction_with_config(
+        &self,
+        tx: &VersionedTransaction,
+        config: RpcSendTransactionConfig,
+    ) -> ClientResult<Signature> {
+        RpcClient::send_transaction_with_config(self, tx, config).await
+    }
+
+    async fn get_signature_statuses(
+        &self,
+        sigs: &[Signature],
+    ) -> RpcResult<Vec<Option<TransactionStatus>>> {
+        RpcClient::get_signature_statuses(self, sigs).await
+    }
+
+    async fn get_signature_statuses_with_history(
+        &self,
+        sigs: &[Signature],
+    ) -> RpcResult<Vec<Option<TransactionStatus>>> {
+        RpcClient::get_signature_statuses_with_history(self, sigs).await
+    }
+}
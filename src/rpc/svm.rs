@@ -0,0 +1,222 @@
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use data_encoding::BASE64;
+use litesvm::{LiteSVM, types::TransactionMetadata};
+use solana_account::{AccountSharedData, ReadableAccount};
+use solana_commitment_config::CommitmentConfig;
+use solana_hash::Hash;
+use solana_pubkey::Pubkey;
+use solana_rpc_client_api::{
+    client_error::{ErrorKind as ClientErrorKind, Result as ClientResult},
+    request::{RpcError, RpcResponseErrorData},
+};
+use solana_rpc_client_types::{
+    config::{RpcSendTransactionConfig, RpcSimulateTransactionConfig},
+    response::{Response, RpcPrioritizationFee, RpcResponseContext, RpcSimulateTransactionResult},
+};
+use solana_signature::Signature;
+use solana_transaction::versioned::VersionedTransaction;
+use solana_transaction_error::TransactionError;
+use solana_transaction_status_client_types::{TransactionConfirmationStatus, TransactionStatus};
+
+use crate::rpc::{Rpc, RpcResult, mock::mock_status};
+
+/// [`Rpc`] that executes transactions in a [`LiteSVM`].
+///
+/// Signatures and blockhashes are not checked so that simulations,
+/// which are unsigned and use a default blockhash, run the same as they would on a cluster.
+/// Transactions land and are finalized as soon as they are sent.
+pub struct SvmRpc(pub Mutex<LiteSVM>);
+
+impl SvmRpc {
+    pub fn new(svm: LiteSVM) -> Self {
+        Self(Mutex::new(
+            svm.with_sigverify(false).with_blockhash_check(false),
+        ))
+    }
+
+    fn ctx<T>(value: T) -> Response<T> {
+        Response {
+            context: RpcResponseContext {
+                slot: 0,
+                api_version: None,
+            },
+            value,
+        }
+    }
+
+    fn statuses(&self, sigs: &[Signature]) -> Vec<Option<TransactionStatus>> {
+        let svm = self.0.lock().unwrap();
+        sigs.iter()
+            .map(|sig| {
+                svm.get_transaction(sig).map(|res| {
+                    mock_status(
+                        TransactionConfirmationStatus::Finalized,
+                        res.as_ref().err().map(|f| f.err.clone()),
+                    )
+                })
+            })
+            .collect()
+    }
+}
+
+/// `accounts` are the requested post-simulation accounts
+fn sim_result(
+    err: Option<TransactionError>,
+    meta: &TransactionMetadata,
+    accounts: Option<Vec<Option<&AccountSharedData>>>,
+) -> RpcSimulateTransactionResult {
+    let accounts = accounts.map(|accs| {
+        accs.into_iter()
+            .map(|acc| {
+                acc.map(|acc| {
+                    serde_json::json!({
+                        "lamports": acc.lamports(),
+                        "data": [BASE64.encode(acc.data()), "base64"],
+                        "owner": acc.owner().to_string(),
+                        "executable": acc.executable(),
+                        "rentEpoch": acc.rent_epoch(),
+                        "space": acc.data().len(),
+                    })
+                })
+            })
+            .collect::<Vec<_>>()
+    });
+    // missing optional fields default to None
+    serde_json::from_value(serde_json::json!({
+        "err": err,
+        "logs": meta.logs,
+        "accounts": accounts,
+        "unitsConsumed": meta.compute_units_consumed,
+        "returnData": null,
+    }))
+    .unwrap()
+}
+
+#[async_trait]
+impl Rpc for SvmRpc {
+    fn url(&self) -> String {
+        "litesvm".to_owned()
+    }
+
+    fn commitment(&self) -> CommitmentConfig {
+        CommitmentConfig::confirmed()
+    }
+
+    async fn get_account_data(&self, key: &Pubkey) -> ClientResult<Vec<u8>> {
+        self.0
+            .lock()
+            .unwrap()
+            .get_account(key)
+            .map(|acc| acc.data)
+            .ok_or_else(|| ClientErrorKind::Custom(format!("AccountNotFound: pubkey={key}")).into())
+    }
+
+    async fn get_account_data_with_commitment(
+        &self,
+        key: &Pubkey,
+        _commitment: CommitmentConfig,
+    ) -> RpcResult<Option<Vec<u8>>> {
+        Ok(Self::ctx(
+            self.0.lock().unwrap().get_account(key).map(|acc| acc.data),
+        ))
+    }
+
+    async fn get_balance(&self, key: &Pubkey) -> ClientResult<u64> {
+        Ok(self.0.lock().unwrap().get_balance(key).unwrap_or(0))
+    }
+
+    async fn get_minimum_balance_for_rent_exemption(&self, len: usize) -> ClientResult<u64> {
+        Ok(self
+            .0
+            .lock()
+            .unwrap()
+            .minimum_balance_for_rent_exemption(len))
+    }
+
+    async fn get_slot_with_commitment(&self, _commitment: CommitmentConfig) -> ClientResult<u64> {
+        Ok(0)
+    }
+
+    async fn get_latest_blockhash(&self) -> ClientResult<Hash> {
+        Ok(self.0.lock().unwrap().latest_blockhash())
+    }
+
+    async fn is_blockhash_valid(
+        &self,
+        blockhash: &Hash,
+        _commitment: CommitmentConfig,
+    ) -> ClientResult<bool> {
+        Ok(*blockhash == self.0.lock().unwrap().latest_blockhash())
+    }
+
+    async fn get_recent_prioritization_fees(
+        &self,
+        _keys: &[Pubkey],
+    ) -> ClientResult<Vec<RpcPrioritizationFee>> {
+        Ok(vec![])
+    }
+
+    async fn simulate_transaction_with_config(
+        &self,
+        tx: &VersionedTransaction,
+        config: RpcSimulateTransactionConfig,
+    ) -> RpcResult<RpcSimulateTransactionResult> {
+        let result = match self.0.lock().unwrap().simulate_transaction(tx.clone()) {
+            Ok(info) => {
+                let accounts = config.accounts.map(|c| {
+                    c.addresses
+                        .iter()
+                        .map(|addr| {
+                            info.post_accounts
+                                .iter()
+                                .find(|(k, _)| k.to_string() == *addr)
+                                .map(|(_, acc)| acc)
+                        })
+                        .collect()
+                });
+                sim_result(None, &info.meta, accounts)
+            }
+            Err(f) => sim_result(Some(f.err), &f.meta, None),
+        };
+        Ok(Self::ctx(result))
+    }
+
+    async fn send_transaction_with_config(
+        &self,
+        tx: &VersionedTransaction,
+        _config: RpcSendTransactionConfig,
+    ) -> ClientResult<Signature> {
+        let mut svm = self.0.lock().unwrap();
+        let res = svm.send_transaction(tx.clone());
+        // so that identical transactions sent later are not rejected as duplicates
+        svm.expire_blockhash();
+        res.map(|meta| meta.signature).map_err(|f| {
+            ClientErrorKind::RpcError(RpcError::RpcResponseError {
+                code: -32002,
+                message: format!("Transaction simulation failed: {}", f.err),
+                data: RpcResponseErrorData::SendTransactionPreflightFailure(sim_result(
+                    Some(f.err),
+                    &f.meta,
+                    None,
+                )),
+            })
+            .into()
+        })
+    }
+
+    async fn get_signature_statuses(
+        &self,
+        sigs: &[Signature],
+    ) -> RpcResult<Vec<Option<TransactionStatus>>> {
+        Ok(Self::ctx(self.statuses(sigs)))
+    }
+
+    async fn get_signature_statuses_with_history(
+        &self,
+        sigs: &[Signature],
+    ) -> RpcResult<Vec<Option<TransactionStatus>>> {
+        Ok(Self::ctx(self.statuses(sigs)))
+    }
+}
@@ -0,0 +1,315 @@
+use std::time::Duration;
+
+use clap::Args;
+use solana_commitment_config::{CommitmentConfig, CommitmentLevel};
+use solana_hash::Hash;
+use solana_pubkey::Pubkey;
+use solana_rpc_client_api::{
+    client_error::{Error as ClientError, ErrorKind as ClientErrorKind},
+    request::{RpcError, RpcResponseErrorData},
+};
+use solana_rpc_client_types::config::RpcSendTransactionConfig;
+use solana_signature::Signature;
+use solana_transaction::versioned::VersionedTransaction;
+use solana_transaction_status_client_types::{TransactionStatus, UiTransactionEncoding};
+use tokio::time::{Instant, sleep};
+
+use crate::{
+    err::{CliErr, CliResult},
+    nonce::{nonce_blockhash, tx_nonce_account},
+    rpc::Rpc,
+};
+
+const REBROADCAST_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Preflight is only run on the first send so that transactions that would fail are never sent
+const SEND_TX_CFG: RpcSendTransactionConfig = RpcSendTransactionConfig {
+    skip_preflight: false,
+    preflight_commitment: Some(CommitmentLevel::Processed),
+    encoding: Some(UiTransactionEncoding::Base64),
+    max_retries: Some(0),
+    min_context_slot: None,
+};
+
+/// Preflight on rebroadcasts fails if the transaction has already been processed
+const REBROADCAST_TX_CFG: RpcSendTransactionConfig = RpcSendTransactionConfig {
+    skip_preflight: true,
+    ..SEND_TX_CFG
+};
+
+#[derive(Args, Debug)]
+pub struct SendLoopArgs {
+    #[arg(
+        long,
+        help = "Times to re-sign a transaction with a fresh blockhash and send it again if it expires without landing",
+        default_value_t = 3
+    )]
+    pub send_retries: u32,
+
+    #[arg(
+        long,
+        help = "Seconds to keep rebroadcasting a transaction for before giving up on it. Transactions using --nonce never expire so they are always rebroadcast until this timeout.",
+        default_value_t = 120
+    )]
+    pub send_timeout: u64,
+}
+
+impl SendLoopArgs {
+    pub fn to_send_loop(&self) -> SendLoop {
+        let Self {
+            send_retries,
+            send_timeout,
+        } = self;
+        SendLoop {
+            retries: *send_retries,
+            timeout: Duration::from_secs(*send_timeout),
+        }
+    }
+}
+
+/// How transactions are sent until they are confirmed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SendLoop {
+    pub retries: u32,
+
+    /// Per attempt
+    pub timeout: Duration,
+}
+
+impl SendLoop {
+    /// Sends the transaction output by `to_tx` until it is confirmed.
+    ///
+    /// If it expires without landing, `to_tx` is called again for a transaction
+    /// signed with a fresh blockhash, up to `self.retries` times.
+    /// A transaction is only considered expired once its signature status confirms it did not land
+    /// so that the instructions are never applied twice.
+    pub async fn send(
+        &self,
+        rpc: &dyn Rpc,
+        mut to_tx: impl AsyncFnMut() -> CliResult<VersionedTransaction>,
+    ) -> CliResult<Signature> {
+        for attempt in 0..=self.retries {
+            if attempt > 0 {
+                eprintln!(
+                    "Transaction expired without landing, retrying ({attempt}/{})",
+                    self.retries
+                );
+            }
+            let tx = to_tx().await?;
+            if let Some(sig) = self.broadcast(rpc, &tx).await? {
+                return Ok(sig);
+            }
+        }
+        Err(CliErr::Onchain(format!(
+            "Transaction expired without landing after {} retries",
+            self.retries
+        )))
+    }
+
+    /// [`Self::send`] for an already signed transaction that cannot be re-signed
+    pub async fn send_signed(
+        &self,
+        rpc: &dyn Rpc,
+        tx: &VersionedTransaction,
+    ) -> CliResult<Signature> {
+        self.broadcast(rpc, tx).await?.ok_or_else(|| {
+            CliErr::Onchain(format!(
+                "Transaction {} expired without landing",
+                tx.signatures[0]
+            ))
+        })
+    }
+
+    /// Rebroadcasts `tx` until it is confirmed or can no longer land.
+    /// Returns `None` if it expired without landing.
+    async fn broadcast(
+        &self,
+        rpc: &dyn Rpc,
+        tx: &VersionedTransaction,
+    ) -> CliResult<Option<Signature>> {
+        let sig = rpc
+            .send_transaction_with_config(tx, SEND_TX_CFG)
+            .await
+            .map_err(send_err)?;
+        eprintln!("Sent {sig}, waiting for confirmation");
+
+        let nonce = tx_nonce_account(tx);
+        let blockhash = *tx.message.recent_blockhash();
+        let start = Instant::now();
+        loop {
+            if start.elapsed() >= self.timeout {
+                return Err(CliErr::Onchain(format!(
+                    "Timed out waiting for {sig} to confirm, it may still land"
+                )));
+            }
+            sleep(REBROADCAST_INTERVAL).await;
+
+            match signature_status(rpc, &sig, false).await {
+                Err(e) => {
+                    eprintln!("Warning: failed to fetch status of {sig}: {e}");
+                    continue;
+                }
+                Ok(Some(TransactionStatus { err: Some(e), .. })) => {
+                    return Err(CliErr::Onchain(format!("Transaction {sig} failed: {e}")));
+                }
+                Ok(Some(status)) => {
+                    if status.satisfies_commitment(CommitmentConfig::confirmed()) {
+                        return Ok(Some(sig));
+                    }
+                    // processed, wait for it to be confirmed
+                    continue;
+                }
+                Ok(None) => (),
+            }
+
+            match can_land(rpc, &blockhash, nonce.as_ref()).await {
+                Err(e) => eprintln!("Warning: failed to check if {sig} expired: {e}"),
+                // it may have landed between the status check and now
+                // and the recent status cache may no longer have it
+                Ok(false) => match signature_status(rpc, &sig, true).await {
+                    Ok(None) => return Ok(None),
+                    Ok(Some(TransactionStatus { err: Some(e), .. })) => {
+                        return Err(CliErr::Onchain(format!("Transaction {sig} failed: {e}")));
+                    }
+                    Ok(Some(status)) => {
+                        if status.satisfies_commitment(CommitmentConfig::confirmed()) {
+                            return Ok(Some(sig));
+                        }
+                        continue;
+                    }
+                    Err(e) => eprintln!("Warning: failed to fetch status of {sig}: {e}"),
+                },
+                Ok(true) => (),
+            }
+
+            // errors are ignored since the status is checked again on the next iteration
+            let _ = rpc
+                .send_transaction_with_config(tx, REBROADCAST_TX_CFG)
+                .await;
+        }
+    }
+}
+
+/// Preflight failures are simulation failures, everything else is an RPC error
+fn send_err(e: ClientError) -> CliErr {
+    match e.kind() {
+        ClientErrorKind::RpcError(RpcError::RpcResponseError {
+            data: RpcResponseErrorData::SendTransactionPreflightFailure(_),
+            ..
+        }) => CliErr::Sim(format!("Transaction failed preflight: {e}")),
+        _ => CliErr::Rpc(format!("Failed to send transaction: {e}")),
+    }
+}
+
+async fn signature_status(
+    rpc: &dyn Rpc,
+    sig: &Signature,
+    search_history: bool,
+) -> Result<Option<TransactionStatus>, String> {
+    let res = if search_history {
+        rpc.get_signature_statuses_with_history(&[*sig]).await
+    } else {
+        rpc.get_signature_statuses(&[*sig]).await
+    };
+    res.map(|r| r.value.into_iter().next().flatten())
+        .map_err(|e| e.to_string())
+}
+
+/// Whether a transaction with `blockhash` can still land.
+/// If `nonce` is set, `blockhash` is its value, which only changes once a transaction using it lands.
+async fn can_land(rpc: &dyn Rpc, blockhash: &Hash, nonce: Option<&Pubkey>) -> Result<bool, String> {
+    match nonce {
+        Some(nonce) => {
+            let data = rpc
+                .get_account_data_with_commitment(nonce, CommitmentConfig::confirmed())
+                .await
+                .map_err(|e| e.to_string())?
+                .value
+                .unwrap_or_default();
+            Ok(nonce_blockhash(&data) == Some(*blockhash))
+        }
+        None => rpc
+            .is_blockhash_valid(blockhash, CommitmentConfig::processed())
+            .await
+            .map_err(|e| e.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_instruction::Instruction;
+    use solana_keypair::Keypair;
+    use solana_signer::Signer;
+
+    use crate::{rpc::mock::MockRpc, utils::to_signed_tx};
+
+    use super::*;
+
+    const SEND_LOOP: SendLoop = SendLoop {
+        retries: 1,
+        timeout: Duration::from_secs(60),
+    };
+
+    /// Returns the result and the number of times the transaction was signed
+    async fn send(send_loop: &SendLoop, rpc: &MockRpc) -> (CliResult<Signature>, usize) {
+        let payer = Keypair::new();
+        let mut n_signed = 0;
+        let res = send_loop
+            .send(rpc, async || {
+                n_signed += 1;
+                let ix = Instruction::new_with_bytes(Pubkey::new_unique(), &[], vec![]);
+                to_signed_tx(
+                    vec![ix],
+                    vec![&payer as &dyn Signer],
+                    rpc.get_latest_blockhash().await?,
+                    &[],
+                )
+            })
+            .await;
+        (res, n_signed)
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn lands() {
+        let rpc = MockRpc::default();
+        let (res, n_signed) = send(&SEND_LOOP, &rpc).await;
+        assert_eq!(res.unwrap(), rpc.sent()[0].signatures[0]);
+        assert_eq!(n_signed, 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn expired_is_resigned() {
+        let rpc = MockRpc {
+            n_dropped: 1,
+            ..Default::default()
+        };
+        let (res, n_signed) = send(&SEND_LOOP, &rpc).await;
+        let sent = rpc.sent();
+        assert_eq!(sent.len(), 2);
+        assert_eq!(res.unwrap(), sent[1].signatures[0]);
+        assert_eq!(n_signed, 2);
+
+        let rpc = MockRpc {
+            n_dropped: 1,
+            ..Default::default()
+        };
+        let no_retries = SendLoop {
+            retries: 0,
+            ..SEND_LOOP
+        };
+        let (res, n_signed) = send(&no_retries, &rpc).await;
+        assert!(matches!(res, Err(CliErr::Onchain(_))));
+        assert_eq!(n_signed, 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn lands_only_in_history() {
+        let rpc = MockRpc {
+            history_only: true,
+            ..Default::default()
+        };
+        let (res, n_signed) = send(&SEND_LOOP, &rpc).await;
+        assert_eq!(res.unwrap(), rpc.sent()[0].signatures[0]);
+        assert_eq!(n_signed, 1);
+    }
+}
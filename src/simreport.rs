@@ -0,0 +1,199 @@
+use inf1_pp_flatslab_core::errs::FlatSlabProgramErr;
+use solana_compute_budget_interface::ID as COMPUTE_BUDGET_PROGRAM_ID;
+use solana_instruction::error::InstructionError;
+use solana_message::VersionedMessage;
+use solana_pubkey::Pubkey;
+use solana_rpc_client_types::response::RpcSimulateTransactionResult;
+use solana_transaction_error::TransactionError;
+
+use crate::syncplan::SyncOp;
+
+/// `ComputeBudgetInstruction::SetComputeUnitLimit` borsh enum index
+const SET_COMPUTE_UNIT_LIMIT_DISCM: u8 = 2;
+
+const DEFAULT_IX_CU_LIMIT: u32 = 200_000;
+
+const MAX_TX_CU_LIMIT: u32 = 1_400_000;
+
+/// Prints a summary of simulating a transaction with `msg` to stderr
+pub fn print_sim_report(msg: &VersionedMessage, result: &RpcSimulateTransactionResult) {
+    let err: Option<TransactionError> = result.err.clone().map(Into::into);
+    match &err {
+        None => eprintln!("Simulation succeeded"),
+        Some(err) => eprintln!("Simulation failed: {err}"),
+    }
+    match result.units_consumed {
+        Some(cus) => eprintln!("Compute units: {cus} / {}", cu_limit(msg)),
+        None => eprintln!("Compute units: unknown / {}", cu_limit(msg)),
+    }
+
+    if let Some(TransactionError::InstructionError(i, ix_err)) = &err {
+        failed_ix_lines(msg, usize::from(*i), ix_err)
+            .iter()
+            .for_each(|l| eprintln!("{l}"));
+    }
+
+    eprintln!("Logs:");
+    result
+        .logs
+        .iter()
+        .flatten()
+        .for_each(|l| eprintln!("  {l}"));
+}
+
+/// Describes the instruction at index `i` of `msg` that failed with `err`,
+/// decoding the error if it is one of the flatslab program's
+fn failed_ix_lines(msg: &VersionedMessage, i: usize, err: &InstructionError) -> Vec<String> {
+    let keys = msg.static_account_keys();
+    let Some(ix) = msg.instructions().get(i) else {
+        return vec![format!("Failed instruction: {i}")];
+    };
+    let program_id = keys[usize::from(ix.program_id_index)];
+    let mut res = vec![format!("Failed instruction: {i}, program {program_id}")];
+    if program_id != Pubkey::new_from_array(crate::PROGRAM_ID) {
+        return res;
+    }
+    if let Some(a) = mint_account_idx(&ix.data).and_then(|i| ix.accounts.get(i)) {
+        res.push(match keys.get(usize::from(*a)) {
+            Some(mint) => format!("Mint: {mint}"),
+            None => "Mint: loaded from lookup table".to_owned(),
+        });
+    }
+    if let InstructionError::Custom(code) = err {
+        res.push(match FlatSlabProgramErr::try_from(*code) {
+            Ok(e) => format!("Error: {e:?}: {e}"),
+            Err(_) => format!("Error: unknown flatslab error {code}"),
+        });
+    }
+    res
+}
+
+/// Index of the mint among the accounts of the flatslab instruction with `data`,
+/// `None` if it is not set_lst_fee or remove_lst.
+/// Instructions are told apart by their discriminator, the first byte of their data.
+fn mint_account_idx(data: &[u8]) -> Option<usize> {
+    const MINT: [u8; 32] = [1; 32];
+    let discm = data.first()?;
+    [
+        SyncOp::SetLstFee {
+            mint: MINT.into(),
+            inp_fee_nanos: 0,
+            out_fee_nanos: 0,
+        },
+        SyncOp::RemoveLst { mint: MINT.into() },
+    ]
+    .iter()
+    .map(|op| op.to_ix(&[0; 32], &[0; 32]))
+    .find(|ix| ix.data.first() == Some(discm))
+    .and_then(|ix| ix.accounts.iter().position(|a| a.pubkey.to_bytes() == MINT))
+}
+
+/// Compute unit limit the transaction requested, or the runtime's default if it did not
+fn cu_limit(msg: &VersionedMessage) -> u32 {
+    let keys = msg.static_account_keys();
+    let mut n_non_cb_ixs = 0u32;
+    for ix in msg.instructions() {
+        if keys[usize::from(ix.program_id_index)] != COMPUTE_BUDGET_PROGRAM_ID {
+            n_non_cb_ixs += 1;
+            continue;
+        }
+        if let [SET_COMPUTE_UNIT_LIMIT_DISCM, limit @ ..] = ix.data.as_slice()
+            && let Ok(limit) = <[u8; 4]>::try_from(limit)
+        {
+            return u32::from_le_bytes(limit).min(MAX_TX_CU_LIMIT);
+        }
+    }
+    (n_non_cb_ixs * DEFAULT_IX_CU_LIMIT).min(MAX_TX_CU_LIMIT)
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_compute_budget_interface::ComputeBudgetInstruction;
+    use solana_hash::Hash;
+    use solana_instruction::Instruction;
+    use solana_message::v0::Message;
+
+    use crate::subcmd::set_admin_ix;
+
+    use super::*;
+
+    #[test]
+    fn failed_ix_lines_decodes_flatslab_errs() {
+        let [admin, mint] = core::array::from_fn(|_| Pubkey::new_unique());
+        let ix = SyncOp::RemoveLst { mint }.to_ix(admin.as_array(), admin.as_array());
+        let msg = VersionedMessage::V0(
+            Message::try_compile(&admin, &[ix], &[], Hash::default()).unwrap(),
+        );
+
+        let (code, e) = (0..64)
+            .find_map(|c| FlatSlabProgramErr::try_from(c).ok().map(|e| (c, e)))
+            .unwrap();
+        assert_eq!(
+            failed_ix_lines(&msg, 0, &InstructionError::Custom(code)),
+            [
+                format!(
+                    "Failed instruction: 0, program {}",
+                    Pubkey::new_from_array(crate::PROGRAM_ID)
+                ),
+                format!("Mint: {mint}"),
+                format!("Error: {e:?}: {e}"),
+            ]
+        );
+        assert_eq!(
+            failed_ix_lines(&msg, 0, &InstructionError::Custom(u32::MAX))[2],
+            format!("Error: unknown flatslab error {}", u32::MAX)
+        );
+        // not a custom error
+        assert_eq!(
+            failed_ix_lines(&msg, 0, &InstructionError::InvalidArgument).len(),
+            2
+        );
+        assert_eq!(
+            failed_ix_lines(&msg, 1, &InstructionError::InvalidArgument),
+            ["Failed instruction: 1"]
+        );
+
+        // set_admin has no mint
+        let new_admin = Pubkey::new_unique();
+        let msg = VersionedMessage::V0(
+            Message::try_compile(
+                &admin,
+                &[set_admin_ix(admin.as_array(), &new_admin)],
+                &[],
+                Hash::default(),
+            )
+            .unwrap(),
+        );
+        assert_eq!(
+            failed_ix_lines(&msg, 0, &InstructionError::Custom(code)),
+            [
+                format!(
+                    "Failed instruction: 0, program {}",
+                    Pubkey::new_from_array(crate::PROGRAM_ID)
+                ),
+                format!("Error: {e:?}: {e}"),
+            ]
+        );
+    }
+
+    #[test]
+    fn cu_limit_requested_or_default() {
+        let payer = Pubkey::new_unique();
+        let other = Instruction::new_with_bytes(Pubkey::new_unique(), &[], vec![]);
+        let msg = |ixs: &[Instruction]| {
+            VersionedMessage::V0(Message::try_compile(&payer, ixs, &[], Hash::default()).unwrap())
+        };
+        assert_eq!(
+            cu_limit(&msg(&[
+                ComputeBudgetInstruction::set_compute_unit_price(5),
+                ComputeBudgetInstruction::set_compute_unit_limit(12_345),
+                other.clone(),
+            ])),
+            12_345
+        );
+        assert_eq!(
+            cu_limit(&msg(&[other.clone(), other])),
+            2 * DEFAULT_IX_CU_LIMIT
+        );
+    }
+}
@@ -9,9 +9,13 @@ use inf1_pp_flatslab_core::typedefs::SlabEntryPacked;
 use serde::{Deserialize, Serialize};
 use solana_pubkey::Pubkey;
 
-mod b58pk;
+use crate::err::{CliErr, CliResult};
+
+pub mod b58pk;
 mod slab_csv_nanos;
 
+pub use slab_csv_nanos::parse_nanos;
+
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub struct SlabCsvEntry {
     #[serde(with = "b58pk")]
@@ -34,30 +38,49 @@ impl From<SlabEntryPacked> for SlabCsvEntry {
     }
 }
 
-pub fn read_slab_csv_file(p: impl AsRef<Path>) -> Vec<SlabCsvEntry> {
+pub fn read_slab_csv_file(p: impl AsRef<Path>) -> CliResult<Vec<SlabCsvEntry>> {
     let f = BufReader::new(
-        File::open(p)
-            .map_err(|e| format!("Failed to read slab csv file: {e}"))
-            .unwrap(),
+        File::open(p).map_err(|e| CliErr::Input(format!("Failed to read slab csv file: {e}")))?,
     );
     read_slab_csv(f)
 }
 
-pub fn read_slab_csv(r: impl Read) -> Vec<SlabCsvEntry> {
+/// Reads the csv file, sorts entries by mint and errs on duplicate mints
+pub fn read_sorted_slab_csv_file(p: impl AsRef<Path>) -> CliResult<Vec<SlabCsvEntry>> {
+    let mut entries = read_slab_csv_file(p)?;
+    entries.sort_unstable_by_key(|e| e.mint);
+
+    if let Some(s) = entries
+        .as_slice()
+        .windows(2)
+        .find(|s| s[0].mint == s[1].mint)
+    {
+        return Err(CliErr::Input(format!("Duplicate mint {}", s[0].mint)));
+    }
+
+    Ok(entries)
+}
+
+pub fn read_slab_csv(r: impl Read) -> CliResult<Vec<SlabCsvEntry>> {
     csv::Reader::from_reader(r)
         .deserialize()
         .try_fold(Vec::new(), |mut v, r| {
-            v.push(r.map_err(|e| format!("Failed to deserialize entry: {e}"))?);
-            Ok::<_, String>(v)
+            v.push(r.map_err(|e| CliErr::Input(format!("Failed to deserialize entry: {e}")))?);
+            Ok(v)
         })
-        .unwrap()
 }
 
-pub fn write_slab_csv(w: impl Write, entries: impl IntoIterator<Item = impl Borrow<SlabCsvEntry>>) {
+pub fn write_slab_csv(
+    w: impl Write,
+    entries: impl IntoIterator<Item = impl Borrow<SlabCsvEntry>>,
+) -> CliResult<()> {
     let mut w = csv::Writer::from_writer(w);
     for r in entries {
-        w.serialize(r.borrow()).unwrap();
+        w.serialize(r.borrow())
+            .map_err(|e| CliErr::Input(format!("Failed to write slab csv: {e}")))?;
     }
+    w.flush()
+        .map_err(|e| CliErr::Input(format!("Failed to write slab csv: {e}")))
 }
 
 #[cfg(test)]
@@ -106,7 +129,8 @@ mod tests {
                 .join("test-fixtures")
                 .join("slab_1")
                 .with_extension("csv"),
-        );
+        )
+        .unwrap();
 
         assert_eq!(a, FIXTURE_1);
     }
@@ -114,7 +138,7 @@ mod tests {
     #[test]
     fn write_fixture_1() {
         let mut buf = Vec::new();
-        write_slab_csv(&mut buf, FIXTURE_1);
+        write_slab_csv(&mut buf, FIXTURE_1).unwrap();
         assert_eq!(
             String::from_utf8(buf).unwrap(),
             r#"mint,inp,out
@@ -139,8 +163,8 @@ So11111111111111111111111111111111111111112,-5000000,6000000
             a in vec(rand_slab_csv_entry(), 0..=37),
         ) {
             let mut buf = Vec::new();
-            write_slab_csv(&mut buf, &a);
-            let read = read_slab_csv(buf.as_slice());
+            write_slab_csv(&mut buf, &a).unwrap();
+            let read = read_slab_csv(buf.as_slice()).unwrap();
             prop_assert_eq!(read, a);
         }
     }
@@ -9,6 +9,32 @@ pub fn serialize<S: Serializer>(v: &i32, s: S) -> Result<S::Ok, S::Error> {
     s.serialize_i32(*v)
 }
 
+/// Parses either raw i32 nanos or `{decimal}%` e.g. 0.1%.
+/// Underscores are allowed for human-readable formatting.
+pub fn parse_nanos(v: &str) -> Result<i32, String> {
+    let v = v.replace("_", "");
+
+    if let Ok(v_i64) = v.parse::<i64>() {
+        return v_i64.try_into().map_err(|e| format!("{e}"));
+    }
+
+    if !v.ends_with("%") {
+        return Err("string did not end with %".to_owned());
+    }
+
+    let pct: f64 = v
+        .split_at(v.len() - 1)
+        .0
+        .parse()
+        .map_err(|e| format!("{e}"))?;
+
+    let nanos = pct * 10_000_000.0;
+
+    (nanos.round() as i64)
+        .try_into()
+        .map_err(|e| format!("{e}"))
+}
+
 pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<i32, D::Error> {
     struct V;
 
@@ -23,22 +49,7 @@ pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<i32, D::Error> {
         where
             E: Error,
         {
-            // allow underscores for human-readable formatting
-            let v = v.replace("_", "");
-
-            if let Ok(v_i64) = v.parse() {
-                return self.visit_i64(v_i64);
-            }
-
-            if !v.ends_with("%") {
-                return Err(Error::custom("string did not end with %"));
-            }
-
-            let pct: f64 = v.split_at(v.len() - 1).0.parse().map_err(Error::custom)?;
-
-            let nanos = pct * 10_000_000.0;
-
-            (nanos.round() as i64).try_into().map_err(Error::custom)
+            parse_nanos(v).map_err(Error::custom)
         }
 
         fn visit_i32<E>(self, v: i32) -> Result<Self::Value, E>
@@ -0,0 +1,245 @@
+use std::fmt;
+
+use inf1_pp_flatslab_core::accounts::Slab;
+
+use crate::slabcsv::SlabCsvEntry;
+
+pub const NANOS_PER_BPS: f64 = 100_000.0;
+pub const NANOS_PER_PCT: f64 = 10_000_000.0;
+
+/// A `set_lst_fee` to be run for `new.mint`.
+/// `old` is `None` if the mint is not on the slab yet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SlabEntrySet {
+    pub old: Option<SlabCsvEntry>,
+    pub new: SlabCsvEntry,
+}
+
+/// The changes required to bring the slab onchain in line with a slab csv
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct SlabDiff {
+    /// Entries that are added or changed, in csv order
+    pub sets: Vec<SlabEntrySet>,
+
+    /// Onchain entries that are missing from the csv.
+    /// Always empty if `remove` was not set.
+    pub removes: Vec<SlabCsvEntry>,
+}
+
+impl SlabDiff {
+    pub fn new(csv: &[SlabCsvEntry], slab: &Slab, remove: bool) -> Self {
+        let curr_entries = slab.entries();
+
+        let sets = csv
+            .iter()
+            .filter_map(|e| {
+                let old = curr_entries
+                    .find_by_mint(e.mint.as_array())
+                    .ok()
+                    .map(|curr| SlabCsvEntry {
+                        mint: e.mint,
+                        inp: curr.inp_fee_nanos(),
+                        out: curr.out_fee_nanos(),
+                    });
+                match old {
+                    Some(old) if old == *e => None,
+                    old => Some(SlabEntrySet { old, new: *e }),
+                }
+            })
+            .collect();
+
+        let removes = if remove {
+            curr_entries
+                .0
+                .iter()
+                .filter(|curr| !csv.iter().any(|e| e.mint.as_array() == curr.mint()))
+                .map(|curr| SlabCsvEntry::from(*curr))
+                .collect()
+        } else {
+            vec![]
+        };
+
+        Self { sets, removes }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sets.is_empty() && self.removes.is_empty()
+    }
+}
+
+impl fmt::Display for SlabDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:<6} {:<44} {:>11} {:>11} {:>26} {:>11} {:>11} {:>26}",
+            "action", "mint", "inp_old", "inp_new", "inp_delta", "out_old", "out_new", "out_delta",
+        )?;
+        for SlabEntrySet { old, new } in self.sets.iter() {
+            let (action, inp_old, out_old, inp_delta, out_delta) = match old {
+                None => ("add", "-".into(), "-".into(), "-".into(), "-".into()),
+                Some(old) => (
+                    "change",
+                    old.inp.to_string(),
+                    old.out.to_string(),
+                    fmt_delta(old.inp, new.inp),
+                    fmt_delta(old.out, new.out),
+                ),
+            };
+            writeln!(
+                f,
+                "{action:<6} {:<44} {inp_old:>11} {:>11} {inp_delta:>26} {out_old:>11} {:>11} {out_delta:>26}",
+                new.mint.to_string(),
+                new.inp,
+                new.out,
+            )?;
+        }
+        for old in self.removes.iter() {
+            writeln!(
+                f,
+                "{:<6} {:<44} {:>11} {:>11} {:>26} {:>11} {:>11} {:>26}",
+                "remove",
+                old.mint.to_string(),
+                old.inp,
+                "-",
+                "-",
+                old.out,
+                "-",
+                "-",
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Formats the change from `old` to `new` nanos as `{bps} bps ({pct}%)`
+fn fmt_delta(old: i32, new: i32) -> String {
+    let delta = f64::from(new) - f64::from(old);
+    format!(
+        "{:+.2} bps ({:+.4}%)",
+        delta / NANOS_PER_BPS,
+        delta / NANOS_PER_PCT
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_pubkey::Pubkey;
+
+    use crate::rpc::mock::mock_slab_data;
+
+    use super::*;
+
+    fn entry(mint: Pubkey, inp: i32, out: i32) -> SlabCsvEntry {
+        SlabCsvEntry { mint, inp, out }
+    }
+
+    #[test]
+    fn diff_added_changed_unchanged_removed() {
+        let mut mints: [Pubkey; 4] = core::array::from_fn(|_| Pubkey::new_unique());
+        mints.sort_unstable();
+        let [added, changed, unchanged, removed] = mints;
+        let slab_d = mock_slab_data(
+            &Pubkey::new_unique(),
+            &[
+                entry(changed, 1, 2),
+                entry(unchanged, 3, 4),
+                entry(removed, 5, 6),
+            ],
+        );
+        let slab = Slab::of_acc_data(&slab_d).unwrap();
+        let csv = [
+            entry(added, 7, 8),
+            entry(changed, 1, 9),
+            entry(unchanged, 3, 4),
+        ];
+        let sets = vec![
+            SlabEntrySet {
+                old: None,
+                new: csv[0],
+            },
+            SlabEntrySet {
+                old: Some(entry(changed, 1, 2)),
+                new: csv[1],
+            },
+        ];
+
+        assert_eq!(
+            SlabDiff::new(&csv, &slab, false),
+            SlabDiff {
+                sets: sets.clone(),
+                removes: vec![],
+            }
+        );
+        assert_eq!(
+            SlabDiff::new(&csv, &slab, true),
+            SlabDiff {
+                sets,
+                removes: vec![entry(removed, 5, 6)],
+            }
+        );
+        assert!(SlabDiff::new(&csv[2..], &slab, false).is_empty());
+    }
+
+    #[test]
+    fn delta_bps_and_pct() {
+        assert_eq!(fmt_delta(0, 100_000), "+1.00 bps (+0.0100%)");
+        assert_eq!(fmt_delta(1_000_000, -1_500_000), "-25.00 bps (-0.2500%)");
+        assert_eq!(fmt_delta(5, 5), "+0.00 bps (+0.0000%)");
+    }
+
+    #[test]
+    fn display_rows() {
+        let [a, b, c] = core::array::from_fn(|_| Pubkey::new_unique());
+        let diff = SlabDiff {
+            sets: vec![
+                SlabEntrySet {
+                    old: None,
+                    new: entry(a, 1, 2),
+                },
+                SlabEntrySet {
+                    old: Some(entry(b, 0, 0)),
+                    new: entry(b, 100_000, 0),
+                },
+            ],
+            removes: vec![entry(c, 3, 4)],
+        };
+        let s = diff.to_string();
+        let rows: Vec<Vec<&str>> = s.lines().map(|l| l.split_whitespace().collect()).collect();
+        assert_eq!(rows.len(), 4);
+        assert_eq!(rows[0][0], "action");
+        assert_eq!(
+            rows[1],
+            ["add", a.to_string().as_str(), "-", "1", "-", "-", "2", "-"]
+        );
+        assert_eq!(
+            rows[2],
+            [
+                "change",
+                b.to_string().as_str(),
+                "0",
+                "100000",
+                "+1.00",
+                "bps",
+                "(+0.0100%)",
+                "0",
+                "0",
+                "+0.00",
+                "bps",
+                "(+0.0000%)"
+            ]
+        );
+        assert_eq!(
+            rows[3],
+            [
+                "remove",
+                c.to_string().as_str(),
+                "3",
+                "-",
+                "-",
+                "4",
+                "-",
+                "-"
+            ]
+        );
+    }
+}
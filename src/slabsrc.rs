@@ -0,0 +1,116 @@
+use std::{fs::read, sync::Arc};
+
+use clap::Args;
+use data_encoding::BASE64;
+use serde::Deserialize;
+
+use crate::{
+    err::{CliErr, CliResult},
+    rpc::Rpc,
+    utils::{check_slab_data, fetch_slab_data},
+};
+
+#[derive(Args, Debug)]
+pub struct SlabSrcArgs {
+    #[arg(
+        long,
+        help = "Read the slab's account data from this file instead of fetching it. Accepts the output of `solana account --output json`, raw bytes or base64. Used by view-prices, view-admin, diff, quote and sync-prices --plan-out. Rejected by set-admin, restore, apply-plan, snapshot, lut and init."
+    )]
+    pub slab_file: Option<Arc<str>>,
+}
+
+impl SlabSrcArgs {
+    /// Slab account data from `--slab-file` if set, otherwise fetched.
+    /// Errs same as [`fetch_slab_data`], except with [`CliErr::Input`] for a file that is not a slab.
+    pub async fn fetch(&self, rpc: &dyn Rpc) -> CliResult<Vec<u8>> {
+        let Some(p) = &self.slab_file else {
+            return fetch_slab_data(rpc).await;
+        };
+        let contents = read(p.as_ref())
+            .map_err(|e| CliErr::Input(format!("Failed to read slab file: {e}")))?;
+        let data = parse_slab_file(&contents).map_err(CliErr::Input)?;
+        check_slab_data(&data).map_err(|e| CliErr::Input(format!("Slab file: {e}")))?;
+        Ok(data)
+    }
+
+    /// Errs if `--slab-file` is set for `cmd`, which must work on the slab onchain
+    pub fn reject(&self, cmd: &str) -> CliResult<()> {
+        match self.slab_file {
+            Some(_) => Err(CliErr::Input(format!(
+                "--slab-file cannot be used with {cmd}"
+            ))),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Format of `solana account --output json`, only the fields needed
+#[derive(Debug, Deserialize)]
+struct AccountJson {
+    account: UiAccountJson,
+}
+
+#[derive(Debug, Deserialize)]
+struct UiAccountJson {
+    /// (encoded data, encoding)
+    data: (String, String),
+}
+
+/// Tries `solana account --output json`, then base64, then falls back to raw bytes
+fn parse_slab_file(contents: &[u8]) -> Result<Vec<u8>, String> {
+    if let Ok(AccountJson {
+        account: UiAccountJson {
+            data: (data, encoding),
+        },
+    }) = serde_json::from_slice(contents)
+    {
+        return match encoding.as_str() {
+            "base64" => BASE64
+                .decode(data.as_bytes())
+                .map_err(|e| format!("Invalid base64 account data: {e}")),
+            "base58" => bs58::decode(&data)
+                .into_vec()
+                .map_err(|e| format!("Invalid base58 account data: {e}")),
+            _ => Err(format!(
+                "Unsupported account data encoding {encoding}, rerun solana account with --output json"
+            )),
+        };
+    }
+    if let Some(data) = std::str::from_utf8(contents)
+        .ok()
+        .and_then(|s| BASE64.decode(s.trim().as_bytes()).ok())
+    {
+        return Ok(data);
+    }
+    Ok(contents.to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{rpc::mock::MockRpc, testcfg::TestCfg};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn fetch_invalid_slab_file() {
+        let cfg = TestCfg::new();
+        let res = SlabSrcArgs {
+            slab_file: Some(cfg.write("slab.bin", [1, 2, 3]).into()),
+        }
+        .fetch(&MockRpc::default())
+        .await;
+        assert!(matches!(res, Err(CliErr::Input(_))));
+    }
+
+    #[test]
+    fn parse_all_formats() {
+        let data: Vec<u8> = (0..=255).collect();
+        let b64 = BASE64.encode(&data);
+        let json = format!(
+            r#"{{"pubkey":"11111111111111111111111111111111","account":{{"lamports":1,"data":["{b64}","base64"],"owner":"11111111111111111111111111111111","executable":false,"rentEpoch":0,"space":256}}}}"#
+        );
+        for contents in [json.as_bytes(), b64.as_bytes(), data.as_slice()] {
+            assert_eq!(parse_slab_file(contents).unwrap(), data);
+        }
+    }
+}
@@ -0,0 +1,157 @@
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter},
+    path::Path,
+};
+
+use inf1_pp_flatslab_core::accounts::Slab;
+use serde::{Deserialize, Serialize};
+use solana_hash::Hash;
+use solana_pubkey::Pubkey;
+
+use crate::{
+    err::{CliErr, CliResult},
+    slabcsv::{SlabCsvEntry, b58pk},
+    slabdiff::SlabDiff,
+    syncplan::{SyncPlan, b58, slab_data_hash},
+};
+
+/// The full state of the slab at a given slot
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SlabSnapshot {
+    #[serde(with = "b58pk")]
+    pub admin: Pubkey,
+
+    /// Slot the slab was fetched at
+    pub slot: u64,
+
+    #[serde(with = "b58")]
+    pub slab_data_hash: Hash,
+
+    pub entries: Vec<SnapshotEntry>,
+}
+
+/// Same as [`SlabCsvEntry`] but with nanos always serialized as numbers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SnapshotEntry {
+    #[serde(with = "b58pk")]
+    pub mint: Pubkey,
+    pub inp_fee_nanos: i32,
+    pub out_fee_nanos: i32,
+}
+
+impl From<SlabCsvEntry> for SnapshotEntry {
+    fn from(SlabCsvEntry { mint, inp, out }: SlabCsvEntry) -> Self {
+        Self {
+            mint,
+            inp_fee_nanos: inp,
+            out_fee_nanos: out,
+        }
+    }
+}
+
+impl From<SnapshotEntry> for SlabCsvEntry {
+    fn from(
+        SnapshotEntry {
+            mint,
+            inp_fee_nanos,
+            out_fee_nanos,
+        }: SnapshotEntry,
+    ) -> Self {
+        Self {
+            mint,
+            inp: inp_fee_nanos,
+            out: out_fee_nanos,
+        }
+    }
+}
+
+impl SlabSnapshot {
+    pub fn new(slot: u64, slab_d: &[u8]) -> Self {
+        let slab = Slab::of_acc_data(slab_d).unwrap();
+        Self {
+            admin: Pubkey::new_from_array(*slab.admin()),
+            slot,
+            slab_data_hash: slab_data_hash(slab_d),
+            entries: slab
+                .entries()
+                .0
+                .iter()
+                .map(|e| SlabCsvEntry::from(*e).into())
+                .collect(),
+        }
+    }
+
+    /// Entries sorted by mint
+    pub fn sorted_csv_entries(&self) -> Vec<SlabCsvEntry> {
+        let mut res: Vec<SlabCsvEntry> = self.entries.iter().map(|e| (*e).into()).collect();
+        res.sort_unstable_by_key(|e| e.mint);
+        res
+    }
+
+    /// Plan that brings the entries of slab `slab_d` back to this snapshot's
+    pub fn restore_plan(&self, slab_d: &[u8]) -> SyncPlan {
+        let slab = Slab::of_acc_data(slab_d).unwrap();
+        SyncPlan::new(
+            slab_d,
+            &SlabDiff::new(&self.sorted_csv_entries(), &slab, true),
+        )
+    }
+}
+
+pub fn read_slab_snapshot_file(p: impl AsRef<Path>) -> CliResult<SlabSnapshot> {
+    let f = BufReader::new(
+        File::open(p)
+            .map_err(|e| CliErr::Input(format!("Failed to read slab snapshot file: {e}")))?,
+    );
+    serde_json::from_reader(f)
+        .map_err(|e| CliErr::Input(format!("Failed to deserialize slab snapshot: {e}")))
+}
+
+pub fn write_slab_snapshot_file(p: impl AsRef<Path>, snapshot: &SlabSnapshot) -> CliResult<()> {
+    let f = BufWriter::new(
+        File::create(p)
+            .map_err(|e| CliErr::Input(format!("Failed to create slab snapshot file: {e}")))?,
+    );
+    serde_json::to_writer_pretty(f, snapshot)
+        .map_err(|e| CliErr::Input(format!("Failed to write slab snapshot: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{rpc::mock::mock_slab_data, syncplan::project_entries};
+
+    use super::*;
+
+    #[test]
+    fn restore_roundtrip() {
+        let admin = Pubkey::new_unique();
+        let [m0, m1, m2] = core::array::from_fn(|_| Pubkey::new_unique());
+        let entry = |mint, inp, out| SlabCsvEntry { mint, inp, out };
+
+        let before = mock_slab_data(&admin, &[entry(m0, 1, 2), entry(m1, -3, 4)]);
+        let snapshot = SlabSnapshot::new(1, &before);
+        let snapshot: SlabSnapshot =
+            serde_json::from_str(&serde_json::to_string(&snapshot).unwrap()).unwrap();
+        assert_eq!(snapshot.admin, admin);
+        assert_eq!(snapshot.slab_data_hash, slab_data_hash(&before));
+
+        // m0 changed, m1 removed, m2 added
+        let after = mock_slab_data(&admin, &[entry(m0, 5, 6), entry(m2, 7, 8)]);
+        let plan = snapshot.restore_plan(&after);
+        assert_eq!(plan.ops.len(), 3);
+
+        let after_slab = Slab::of_acc_data(&after).unwrap();
+        let restored = project_entries(
+            after_slab
+                .entries()
+                .0
+                .iter()
+                .map(|e| SlabCsvEntry::from(*e)),
+            &plan.ops,
+        );
+        assert_eq!(restored, snapshot.sorted_csv_entries());
+
+        assert!(snapshot.restore_plan(&before).ops.is_empty());
+    }
+}
@@ -0,0 +1,318 @@
+//! Minimal Squads v4 client for wrapping instructions into vault transaction proposals.
+//! Hand-rolled to avoid pulling in anchor and the squads sdk's solana-1.X deps.
+
+use std::sync::{Arc, Mutex};
+
+use clap::{Args, builder::ValueParser};
+use solana_cli_config::Config;
+use solana_commitment_config::CommitmentConfig;
+use solana_instruction::{AccountMeta, Instruction};
+use solana_message::{Message, MessageHeader};
+use solana_pubkey::Pubkey;
+use solana_signer::Signer;
+
+use crate::{
+    err::{CliErr, CliResult},
+    rpc::Rpc,
+    sscu::{parse_pubkey_from_src, parse_signer},
+};
+
+pub const SQUADS_PROGRAM_ID: Pubkey =
+    Pubkey::from_str_const("SQDS4ep65T869zMMBKyuUq6aD6EgTu8psMjkvj52pCf");
+
+const SYSTEM_PROGRAM_ID: Pubkey = Pubkey::new_from_array([0u8; 32]);
+
+/// sha256("global:vault_transaction_create")[..8]
+const VAULT_TRANSACTION_CREATE_DISCM: [u8; 8] = [48, 250, 78, 168, 208, 226, 218, 211];
+
+/// sha256("global:proposal_create")[..8]
+const PROPOSAL_CREATE_DISCM: [u8; 8] = [220, 60, 73, 224, 30, 108, 79, 159];
+
+/// sha256("account:Multisig")[..8]
+pub const MULTISIG_ACC_DISCM: [u8; 8] = [224, 116, 121, 186, 68, 161, 79, 236];
+
+/// discm, create_key, config_authority, threshold: u16, time_lock: u32
+const MULTISIG_TRANSACTION_INDEX_OFFSET: usize = 8 + 32 + 32 + 2 + 4;
+
+#[derive(Args, Debug)]
+pub struct SquadsArgs {
+    #[arg(
+        long,
+        help = "Squads v4 multisig PDA whose vault is the slab admin. Required for --send-mode squads.",
+        value_parser = ValueParser::new(parse_pubkey_from_src)
+    )]
+    pub squads_multisig: Option<Pubkey>,
+
+    #[arg(long, help = "Index of the multisig vault", default_value_t = 0)]
+    pub squads_vault_index: u8,
+
+    #[arg(
+        long,
+        help = "Path to keypair of the multisig member that creates and pays for proposals. Defaults to config wallet if not set."
+    )]
+    pub squads_member: Option<Arc<str>>,
+}
+
+pub struct Squads {
+    pub multisig: Pubkey,
+    pub vault_index: u8,
+
+    /// Creator and rent payer of proposals
+    pub member: Box<dyn Signer>,
+
+    /// Set once fetched, then tracked by [`Self::advance`]
+    /// since the RPC may lag behind the vault transactions this run has created
+    pub next_tx_index: Mutex<Option<u64>>,
+}
+
+impl SquadsArgs {
+    pub fn to_squads(&self, config: &Config) -> CliResult<Squads> {
+        let Self {
+            squads_multisig,
+            squads_vault_index,
+            squads_member,
+        } = self;
+        let multisig = squads_multisig.ok_or_else(|| {
+            CliErr::Config("--squads-multisig is required for --send-mode squads".to_owned())
+        })?;
+        let member = parse_signer(
+            squads_member
+                .as_deref()
+                .unwrap_or(config.keypair_path.as_str()),
+        )
+        .map_err(CliErr::Config)?;
+        Ok(Squads {
+            multisig,
+            vault_index: *squads_vault_index,
+            member,
+            next_tx_index: Mutex::new(None),
+        })
+    }
+}
+
+impl Squads {
+    pub fn vault(&self) -> Pubkey {
+        Pubkey::find_program_address(
+            &[
+                b"multisig",
+                self.multisig.as_ref(),
+                b"vault",
+                &[self.vault_index],
+            ],
+            &SQUADS_PROGRAM_ID,
+        )
+        .0
+    }
+
+    pub fn transaction(&self, tx_index: u64) -> Pubkey {
+        Pubkey::find_program_address(
+            &[
+                b"multisig",
+                self.multisig.as_ref(),
+                b"transaction",
+                &tx_index.to_le_bytes(),
+            ],
+            &SQUADS_PROGRAM_ID,
+        )
+        .0
+    }
+
+    pub fn proposal(&self, tx_index: u64) -> Pubkey {
+        Pubkey::find_program_address(
+            &[
+                b"multisig",
+                self.multisig.as_ref(),
+                b"transaction",
+                &tx_index.to_le_bytes(),
+                b"proposal",
+            ],
+            &SQUADS_PROGRAM_ID,
+        )
+        .0
+    }
+
+    /// Index the next vault transaction created for the multisig will have
+    pub async fn next_tx_index(&self, rpc: &dyn Rpc) -> CliResult<u64> {
+        if let Some(idx) = *self.next_tx_index.lock().unwrap() {
+            return Ok(idx);
+        }
+        let data = rpc
+            .get_account_data_with_commitment(&self.multisig, CommitmentConfig::confirmed())
+            .await?
+            .value
+            .unwrap_or_default();
+        let idx: [u8; 8] = data
+            .get(MULTISIG_TRANSACTION_INDEX_OFFSET..MULTISIG_TRANSACTION_INDEX_OFFSET + 8)
+            .filter(|_| data.get(..8) == Some(MULTISIG_ACC_DISCM.as_slice()))
+            .ok_or_else(|| CliErr::Config(format!("{} is not a squads multisig", self.multisig)))?
+            .try_into()
+            .unwrap();
+        let idx = u64::from_le_bytes(idx) + 1;
+        *self.next_tx_index.lock().unwrap() = Some(idx);
+        Ok(idx)
+    }
+
+    /// Marks the vault transaction at [`Self::next_tx_index`] as created.
+    /// Must only be called once the transaction creating it has confirmed.
+    pub fn advance(&self) {
+        if let Some(idx) = self.next_tx_index.lock().unwrap().as_mut() {
+            *idx += 1;
+        }
+    }
+
+    /// Returns the `vault_transaction_create` and `proposal_create` instructions
+    /// that propose running `ixs` with the vault as payer
+    pub fn wrap_ixs(&self, tx_index: u64, ixs: &[Instruction]) -> [Instruction; 2] {
+        let member = self.member.pubkey();
+        let tx_msg = vault_tx_message(&self.vault(), ixs);
+
+        let mut vtc_data = VAULT_TRANSACTION_CREATE_DISCM.to_vec();
+        vtc_data.push(self.vault_index);
+        vtc_data.push(0); // ephemeral_signers
+        vtc_data.extend(u32::try_from(tx_msg.len()).unwrap().to_le_bytes());
+        vtc_data.extend(tx_msg);
+        vtc_data.push(0); // memo: None
+
+        let mut pc_data = PROPOSAL_CREATE_DISCM.to_vec();
+        pc_data.extend(tx_index.to_le_bytes());
+        pc_data.push(0); // draft: false
+
+        [
+            Instruction::new_with_bytes(
+                SQUADS_PROGRAM_ID,
+                &vtc_data,
+                vec![
+                    AccountMeta::new(self.multisig, false),
+                    AccountMeta::new(self.transaction(tx_index), false),
+                    AccountMeta::new_readonly(member, true),
+                    AccountMeta::new(member, true),
+                    AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+                ],
+            ),
+            Instruction::new_with_bytes(
+                SQUADS_PROGRAM_ID,
+                &pc_data,
+                vec![
+                    AccountMeta::new_readonly(self.multisig, false),
+                    AccountMeta::new(self.proposal(tx_index), false),
+                    AccountMeta::new_readonly(member, true),
+                    AccountMeta::new(member, true),
+                    AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+                ],
+            ),
+        ]
+    }
+}
+
+/// Serializes `ixs` into squads' `TransactionMessage` format,
+/// which is a legacy message with `SmallVec` lengths
+/// and an always-empty list of address table lookups
+fn vault_tx_message(vault: &Pubkey, ixs: &[Instruction]) -> Vec<u8> {
+    let Message {
+        header:
+            MessageHeader {
+                num_required_signatures,
+                num_readonly_signed_accounts,
+                num_readonly_unsigned_accounts,
+            },
+        account_keys,
+        instructions,
+        ..
+    } = Message::new(ixs, Some(vault));
+    let n_keys = u8::try_from(account_keys.len()).unwrap();
+
+    let mut res = vec![
+        num_required_signatures,
+        num_required_signatures - num_readonly_signed_accounts,
+        n_keys - num_required_signatures - num_readonly_unsigned_accounts,
+        n_keys,
+    ];
+    account_keys
+        .iter()
+        .for_each(|k| res.extend_from_slice(k.as_ref()));
+    res.push(u8::try_from(instructions.len()).unwrap());
+    instructions.iter().for_each(|ix| {
+        res.push(ix.program_id_index);
+        res.push(u8::try_from(ix.accounts.len()).unwrap());
+        res.extend_from_slice(&ix.accounts);
+        res.extend(u16::try_from(ix.data.len()).unwrap().to_le_bytes());
+        res.extend_from_slice(&ix.data);
+    });
+    res.push(0); // address_table_lookups
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_keypair::Keypair;
+    use solana_sha256_hasher::hash;
+
+    use crate::rpc::mock::{MockRpc, mock_multisig};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn next_tx_index_tracks_created() {
+        let (multisig, data) = mock_multisig(&Pubkey::new_unique(), &[], 5);
+        // never updated, like a lagging rpc
+        let rpc = MockRpc::default().with_account(multisig, data);
+        let squads = Squads {
+            multisig,
+            vault_index: 0,
+            member: Box::new(Keypair::new()),
+            next_tx_index: Mutex::new(None),
+        };
+
+        assert_eq!(squads.next_tx_index(&rpc).await.unwrap(), 6);
+        // retries reuse the index
+        assert_eq!(squads.next_tx_index(&rpc).await.unwrap(), 6);
+        squads.advance();
+        assert_eq!(squads.next_tx_index(&rpc).await.unwrap(), 7);
+    }
+
+    #[test]
+    fn discms_match_anchor() {
+        for (discm, preimage) in [
+            (
+                VAULT_TRANSACTION_CREATE_DISCM,
+                "global:vault_transaction_create",
+            ),
+            (PROPOSAL_CREATE_DISCM, "global:proposal_create"),
+            (MULTISIG_ACC_DISCM, "account:Multisig"),
+        ] {
+            assert_eq!(discm, hash(preimage.as_bytes()).to_bytes()[..8]);
+        }
+    }
+
+    #[test]
+    fn vault_tx_message_layout() {
+        let vault = Pubkey::new_unique();
+        let [writable, readonly, program] = core::array::from_fn(|_| Pubkey::new_unique());
+        let ix = Instruction::new_with_bytes(
+            program,
+            &[1, 2, 3],
+            vec![
+                AccountMeta::new_readonly(vault, true),
+                AccountMeta::new(writable, false),
+                AccountMeta::new_readonly(readonly, false),
+            ],
+        );
+        let msg = vault_tx_message(&vault, &[ix]);
+
+        // vault is always writable since its the message payer
+        assert_eq!(msg[..4], [1, 1, 1, 4]);
+        assert_eq!(msg[4..36], vault.to_bytes());
+        assert_eq!(msg[36..68], writable.to_bytes());
+        let ixs_start = 4 + 4 * 32;
+        assert_eq!(
+            msg[ixs_start..],
+            [
+                1, // n ixs
+                3, // program_id_index
+                3, 0, 1, 2, // accounts
+                3, 0, 1, 2, 3, // data
+                0  // address_table_lookups
+            ]
+        );
+    }
+}
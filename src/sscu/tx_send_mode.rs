@@ -5,6 +5,10 @@ use clap::ValueEnum;
 /// - `SimOnly` simulates the transaction against the cluster
 /// - `Dump64` outputs base64 encoded serialized transaction to stdout for use with multisigs, explorer inspectors, or piping into other applications
 /// - `Dump58` outputs base58 encoded serialized transaction to stdout for use with multisigs, explorer inspectors, or piping into other applications
+/// - `DumpMsg` outputs base64 encoded serialized message, without signatures, to stdout for use with governance and multisig tooling
+/// - `DumpIxs` outputs the transaction's instructions, without compute budget or nonce instructions, as a json array to stdout for use with governance and multisig tooling
+/// - `Squads` sends a transaction that creates a Squads v4 vault transaction and proposal for the instructions instead of running them
+/// - `Realms` sends a transaction that inserts the instructions into an SPL Governance proposal instead of running them
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, ValueEnum)]
 pub enum TxSendMode {
     #[default]
@@ -12,4 +16,8 @@ pub enum TxSendMode {
     SimOnly,
     Dump64,
     Dump58,
+    DumpMsg,
+    DumpIxs,
+    Squads,
+    Realms,
 }
@@ -0,0 +1,77 @@
+use std::sync::Arc;
+
+use clap::Args;
+use inf1_pp_flatslab_core::accounts::Slab;
+
+use crate::{
+    err::{CliErr, CliResult},
+    rpc::Rpc,
+    slabcsv::SlabCsvEntry,
+    syncplan::{SyncExec, op_entry_sets, project_entries, read_sync_plan_file, slab_data_hash},
+    utils::{TxCtxSrc, fetch_slab_data},
+    verify::VerifyArgs,
+};
+
+#[derive(Args, Debug)]
+#[command(
+    long_about = "Runs a sync plan output by sync-prices --plan-out. Refuses to run if the slab onchain has changed since the plan was computed. The plan is verified again since it may have been edited during review."
+)]
+pub struct ApplyPlanArgs {
+    #[command(flatten)]
+    pub verify: VerifyArgs,
+
+    #[arg(
+        long,
+        short,
+        help = "Path to admin keypair signer. Defaults to config wallet if not set."
+    )]
+    pub admin: Option<Arc<str>>,
+
+    #[arg(help = "Path to sync plan json file")]
+    pub plan: Arc<str>,
+}
+
+impl ApplyPlanArgs {
+    pub async fn run(&self, args: &crate::Args, rpc: &dyn Rpc) -> CliResult<()> {
+        let crate::Args { yes, slab_src, .. } = args;
+        slab_src.reject("apply-plan")?;
+
+        let Self {
+            verify,
+            admin,
+            plan,
+        } = self;
+
+        let plan = read_sync_plan_file(plan.as_ref())?;
+
+        let src = TxCtxSrc::from_args(args, admin.as_deref(), rpc).await?;
+
+        let slab_d = fetch_slab_data(rpc).await?;
+        let curr_hash = slab_data_hash(&slab_d);
+        if curr_hash != plan.slab_data_hash {
+            return Err(CliErr::Verify(format!(
+                "Slab data hash {curr_hash} does not match plan's {}, slab has changed since plan was computed",
+                plan.slab_data_hash
+            )));
+        }
+
+        let slab = Slab::of_acc_data(&slab_d).unwrap();
+        verify.check(
+            &project_entries(
+                slab.entries().0.iter().map(|e| SlabCsvEntry::from(*e)),
+                &plan.ops,
+            ),
+            &op_entry_sets(&slab, &plan.ops),
+        )?;
+
+        SyncExec {
+            slab_d: &slab_d,
+            payer: src.payer.as_ref(),
+            admin: src.admin(),
+            ctx: src.ctx(args, rpc),
+            yes: *yes,
+        }
+        .exec(&plan.ops)
+        .await
+    }
+}
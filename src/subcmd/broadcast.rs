@@ -0,0 +1,55 @@
+use std::sync::Arc;
+
+use clap::Args;
+
+use crate::{
+    confirm::SendSummary,
+    err::{CliErr, CliResult},
+    rpc::Rpc,
+    txser::{invalid_signers, parse_tx_arg},
+    utils::handle_tx,
+};
+
+#[derive(Args, Debug)]
+#[command(
+    long_about = "Sends a fully signed transaction output by sign, handling it according to --send-mode. Refuses to send if any signature is missing or invalid."
+)]
+pub struct BroadcastArgs {
+    #[arg(help = "Base64 or base58 serialized transaction, or path to a file containing one")]
+    pub tx: Arc<str>,
+}
+
+impl BroadcastArgs {
+    pub async fn run(
+        &self,
+        crate::Args {
+            send_mode,
+            yes,
+            send_loop,
+            ..
+        }: &crate::Args,
+        rpc: &dyn Rpc,
+    ) -> CliResult<()> {
+        let Self { tx } = self;
+
+        let (tx, _) = parse_tx_arg(tx).map_err(CliErr::Input)?;
+        let invalid = invalid_signers(&tx);
+        if !invalid.is_empty() {
+            eprintln!("Missing or invalid signatures from:");
+            invalid.iter().for_each(|pk| eprintln!("{pk}"));
+            return Err(CliErr::Input(
+                "Refusing to send partially signed transaction".to_owned(),
+            ));
+        }
+
+        let n_signers = usize::from(tx.message.header().num_required_signatures);
+        SendSummary {
+            rpc,
+            signers: &tx.message.static_account_keys()[..n_signers],
+            n_txs: 1,
+        }
+        .confirm(*send_mode, *yes)?;
+
+        handle_tx(rpc, *send_mode, &send_loop.to_send_loop(), &tx).await
+    }
+}
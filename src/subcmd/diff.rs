@@ -0,0 +1,51 @@
+use std::sync::Arc;
+
+use clap::Args;
+use inf1_pp_flatslab_core::accounts::Slab;
+
+use crate::{
+    err::{CliErr, CliResult},
+    rpc::Rpc,
+    slabcsv::read_sorted_slab_csv_file,
+    slabdiff::SlabDiff,
+};
+
+#[derive(Args, Debug)]
+#[command(
+    long_about = "Outputs the changes sync-prices would make to the slab onchain without building any transactions. Exits with code 8 if there are any changes, 0 otherwise."
+)]
+pub struct DiffArgs {
+    #[arg(
+        long,
+        short,
+        help = "If flag set, include entries that are missing from the csv, which sync-prices --remove would delete",
+        default_value_t = false
+    )]
+    pub remove: bool,
+
+    #[arg(help = "Path to slab prices csv file", default_value_t = {"slab.csv".into()})]
+    pub csv: Arc<str>,
+}
+
+impl DiffArgs {
+    pub async fn run(
+        &self,
+        crate::Args { slab_src, .. }: &crate::Args,
+        rpc: &dyn Rpc,
+    ) -> CliResult<()> {
+        let Self { remove, csv } = self;
+
+        let entries = read_sorted_slab_csv_file(csv.as_ref())?;
+
+        let slab_d = slab_src.fetch(rpc).await?;
+        let slab = Slab::of_acc_data(&slab_d).unwrap();
+
+        let diff = SlabDiff::new(&entries, &slab, *remove);
+        if diff.is_empty() {
+            eprintln!("No changes");
+            return Ok(());
+        }
+        print!("{diff}");
+        Err(CliErr::Diff("Slab differs from csv".to_owned()))
+    }
+}
@@ -0,0 +1,217 @@
+//! Runs subcommands against the real flatslab and Squads v4 programs in LiteSVM through [`SvmRpc`].
+//!
+//! Only built with `--features e2e` since it requires the program binaries in `test-fixtures`, see README.
+
+use std::sync::Mutex;
+
+use inf1_pp_flatslab_core::{accounts::Slab, keys::SLAB_ID};
+use litesvm::LiteSVM;
+use solana_account::Account;
+use solana_keypair::Keypair;
+use solana_pubkey::Pubkey;
+use solana_signer::{Signer, null_signer::NullSigner};
+
+use crate::{
+    proposer::Proposer,
+    rpc::{mock::mock_multisig, svm::SvmRpc},
+    slabcsv::SlabCsvEntry,
+    slabdiff::SlabDiff,
+    squads::{SQUADS_PROGRAM_ID, Squads},
+    sscu::TxSendMode,
+    subcmd::Subcmd,
+    syncplan::SyncOp,
+    testcfg::{TestCfg, test_ctx},
+    utils::TxCtx,
+};
+
+const PROGRAM_SO_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/test-fixtures/flatslab.so");
+
+const SQUADS_SO_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/test-fixtures/squads.so");
+
+const DUMP_SCRIPT: &str = include_str!("../../test-fixtures/dump-programs.sh");
+
+fn add_program(svm: &mut LiteSVM, program_id: Pubkey, path: &str) {
+    svm.add_program_from_file(program_id, path)
+        .unwrap_or_else(|e| panic!("Failed to load {path}: {e}. See README."));
+}
+
+/// Flatslab program deployed with the config wallet funded
+fn init_svm(cfg: &TestCfg) -> SvmRpc {
+    let mut svm = LiteSVM::new();
+    add_program(
+        &mut svm,
+        Pubkey::new_from_array(crate::PROGRAM_ID),
+        PROGRAM_SO_PATH,
+    );
+    svm.airdrop(&cfg.wallet.pubkey(), 1_000_000_000_000)
+        .unwrap();
+    SvmRpc::new(svm)
+}
+
+/// Runs `flatslab --config <cfg> --yes <args>`
+async fn run(cfg: &TestCfg, rpc: &SvmRpc, args: &[&str]) {
+    Subcmd::run(cfg.args(args), rpc).await.unwrap();
+}
+
+fn slab_data(rpc: &SvmRpc) -> Vec<u8> {
+    rpc.0
+        .lock()
+        .unwrap()
+        .get_account(&Pubkey::new_from_array(SLAB_ID))
+        .unwrap()
+        .data
+}
+
+fn slab_admin(rpc: &SvmRpc) -> Pubkey {
+    Pubkey::new_from_array(*Slab::of_acc_data(&slab_data(rpc)).unwrap().admin())
+}
+
+fn slab_entries(rpc: &SvmRpc) -> Vec<SlabCsvEntry> {
+    let slab_d = slab_data(rpc);
+    let mut res: Vec<_> = Slab::of_acc_data(&slab_d)
+        .unwrap()
+        .entries()
+        .0
+        .iter()
+        .map(|e| SlabCsvEntry::from(*e))
+        .collect();
+    res.sort_unstable_by_key(|e| e.mint);
+    res
+}
+
+fn sorted_entries(n: usize) -> Vec<SlabCsvEntry> {
+    let mut res: Vec<_> = (0..n)
+        .map(|i| {
+            let i = i32::try_from(i).unwrap();
+            SlabCsvEntry {
+                mint: Pubkey::new_unique(),
+                inp: i * 1_000,
+                out: i * 1_000,
+            }
+        })
+        .collect();
+    res.sort_unstable_by_key(|e| e.mint);
+    res
+}
+
+#[tokio::test(start_paused = true)]
+async fn init_then_set_admin() {
+    let cfg = TestCfg::new();
+    let rpc = init_svm(&cfg);
+    run(&cfg, &rpc, &["init"]).await;
+    assert_eq!(slab_admin(&rpc), cfg.wallet.pubkey());
+    assert!(slab_entries(&rpc).is_empty());
+
+    let new_admin = Pubkey::new_unique();
+    run(&cfg, &rpc, &["set-admin", &new_admin.to_string()]).await;
+    assert_eq!(slab_admin(&rpc), new_admin);
+}
+
+#[tokio::test(start_paused = true)]
+async fn sync_prices_with_and_without_remove() {
+    let cfg = TestCfg::new();
+    let rpc = init_svm(&cfg);
+    run(&cfg, &rpc, &["init"]).await;
+
+    // enough entries to span multiple transactions
+    let initial = sorted_entries(40);
+    let csv = cfg.write_csv("initial.csv", &initial);
+    run(&cfg, &rpc, &["sync-prices", &csv]).await;
+    assert_eq!(slab_entries(&rpc), initial);
+
+    let mut updated: Vec<_> = initial[..2]
+        .iter()
+        .map(|e| SlabCsvEntry {
+            inp: e.inp + 1,
+            ..*e
+        })
+        .chain(sorted_entries(3))
+        .collect();
+    updated.sort_unstable_by_key(|e| e.mint);
+    let csv = cfg.write_csv("updated.csv", &updated);
+
+    // entries missing from the csv are kept
+    run(&cfg, &rpc, &["sync-prices", &csv]).await;
+    let mut expected: Vec<_> = updated.iter().chain(initial[2..].iter()).copied().collect();
+    expected.sort_unstable_by_key(|e| e.mint);
+    assert_eq!(slab_entries(&rpc), expected);
+
+    // journaled
+    let journal = cfg.path("journal.json");
+    run(
+        &cfg,
+        &rpc,
+        &["sync-prices", "--remove", "--journal", &journal, &csv],
+    )
+    .await;
+    assert_eq!(slab_entries(&rpc), updated);
+
+    // no-op once in sync
+    let slab_d = slab_data(&rpc);
+    let slab = Slab::of_acc_data(&slab_d).unwrap();
+    assert!(SlabDiff::new(&updated, &slab, true).is_empty());
+}
+
+#[tokio::test(start_paused = true)]
+async fn squads_proposals_consecutive_indices() {
+    let mut svm = LiteSVM::new();
+    add_program(&mut svm, SQUADS_PROGRAM_ID, SQUADS_SO_PATH);
+    let member = Keypair::new();
+    svm.airdrop(&member.pubkey(), 1_000_000_000_000).unwrap();
+    let (multisig, data) = mock_multisig(&Pubkey::new_unique(), &[member.pubkey()], 0);
+    svm.set_account(
+        multisig,
+        Account {
+            lamports: svm.minimum_balance_for_rent_exemption(data.len()),
+            data,
+            owner: SQUADS_PROGRAM_ID,
+            executable: false,
+            rent_epoch: 0,
+        },
+    )
+    .unwrap();
+
+    let rpc = SvmRpc::new(svm);
+    let squads = Squads {
+        multisig,
+        vault_index: 0,
+        member: Box::new(member),
+        next_tx_index: Mutex::new(None),
+    };
+    let vault = squads.vault();
+    let proposer = Proposer::Squads(squads);
+    let ctx = TxCtx {
+        proposer: Some(&proposer),
+        ..test_ctx(&rpc, TxSendMode::Squads)
+    };
+    let vault_signer = NullSigner::new(&vault);
+    for _ in 0..2 {
+        let ix = SyncOp::SetLstFee {
+            mint: Pubkey::new_unique(),
+            inp_fee_nanos: 1,
+            out_fee_nanos: 2,
+        }
+        .to_ix(vault.as_array(), vault.as_array());
+        ctx.send(vec![ix], vec![&vault_signer, &vault_signer])
+            .await
+            .unwrap();
+    }
+
+    let Proposer::Squads(squads) = &proposer else {
+        unreachable!()
+    };
+    let svm = rpc.0.lock().unwrap();
+    // proposal_create fails for indices the multisig has not reached
+    for idx in 1..=2 {
+        assert!(svm.get_account(&squads.transaction(idx)).is_some());
+        assert!(svm.get_account(&squads.proposal(idx)).is_some());
+    }
+    assert!(svm.get_account(&squads.transaction(3)).is_none());
+}
+
+#[test]
+fn dump_script_program_ids() {
+    for id in [Pubkey::new_from_array(crate::PROGRAM_ID), SQUADS_PROGRAM_ID] {
+        assert!(DUMP_SCRIPT.contains(&id.to_string()), "{id}");
+    }
+}
@@ -1,14 +1,19 @@
+use std::slice;
+
 use clap::Args;
 use inf1_pp_flatslab_core::{
     instructions::init::{INIT_IX_IS_SIGNER, INIT_IX_IS_WRITER, InitIxData, NewInitIxAccsBuilder},
     keys::SLAB_ID,
 };
 use solana_instruction::Instruction;
-use solana_rpc_client::nonblocking::rpc_client::RpcClient;
+use solana_pubkey::Pubkey;
 
 use crate::{
-    sscu::parse_signer,
-    utils::{handle_tx, keys_signer_writable_to_metas, to_signed_tx, with_auto_cb},
+    confirm::SendSummary,
+    costs::{LamportCosts, sim_slab_lamports, tx_fees},
+    err::CliResult,
+    rpc::Rpc,
+    utils::{TxCtxSrc, keys_signer_writable_to_metas},
 };
 
 #[derive(Args, Debug)]
@@ -16,37 +21,64 @@ use crate::{
 pub struct InitArgs;
 
 impl InitArgs {
-    pub async fn run(
-        &self,
-        crate::Args {
-            config,
+    pub async fn run(&self, args: &crate::Args, rpc: &dyn Rpc) -> CliResult<()> {
+        let crate::Args {
             send_mode,
-            fee_cb,
+            yes,
+            slab_src,
             ..
-        }: &crate::Args,
-    ) {
-        let rpc = RpcClient::new(config.json_rpc_url.to_owned());
-        let signer = parse_signer(&config.keypair_path).unwrap();
+        } = args;
+        slab_src.reject("init")?;
+
+        let src = TxCtxSrc::from_args(args, None, rpc).await?;
+        let ctx = src.ctx(args, rpc);
+        let signer = src.payer.as_ref();
         let signer_pk = signer.pubkey();
 
-        let init_ix = Instruction::new_with_bytes(
-            crate::PROGRAM_ID.into(),
-            InitIxData::new().as_buf(),
-            keys_signer_writable_to_metas(
-                NewInitIxAccsBuilder::start()
-                    .with_payer(signer_pk.to_bytes())
-                    .with_slab(SLAB_ID)
-                    .with_system_program([0u8; 32])
-                    .build()
-                    .0
-                    .iter(),
-                INIT_IX_IS_SIGNER.0.iter(),
-                INIT_IX_IS_WRITER.0.iter(),
-            ),
-        );
-
-        let ixs = with_auto_cb(vec![init_ix], &signer_pk, &rpc, *send_mode, *fee_cb).await;
-        let tx = to_signed_tx(ixs, vec![&signer], &rpc).await;
-        handle_tx(&rpc, *send_mode, &tx).await;
+        let init_ix = init_ix(&signer_pk);
+
+        let signer_pks = ctx.signer_pks(&[signer_pk]);
+
+        let rent_paid = sim_slab_lamports(rpc, &signer_pk, slice::from_ref(&init_ix))
+            .await?
+            .unwrap_or_else(|| {
+                eprintln!("Warning: failed to simulate init, rent paid unknown");
+                0
+            });
+        LamportCosts {
+            rent_paid,
+            rent_refunded: 0,
+            tx_fees: tx_fees([signer_pks.len()], *send_mode, ctx.prio_fee.max_lamports()),
+        }
+        .check_payers(rpc, &ctx.fee_payer(&signer_pk), &signer_pk, *send_mode)
+        .await?;
+
+        eprintln!("Initializing slab");
+        SendSummary {
+            rpc,
+            signers: &signer_pks,
+            n_txs: 1,
+        }
+        .confirm(*send_mode, *yes)?;
+
+        ctx.send(vec![init_ix], vec![signer]).await
     }
 }
+
+pub fn init_ix(payer: &Pubkey) -> Instruction {
+    Instruction::new_with_bytes(
+        crate::PROGRAM_ID.into(),
+        InitIxData::new().as_buf(),
+        keys_signer_writable_to_metas(
+            NewInitIxAccsBuilder::start()
+                .with_payer(payer.to_bytes())
+                .with_slab(SLAB_ID)
+                .with_system_program([0u8; 32])
+                .build()
+                .0
+                .iter(),
+            INIT_IX_IS_SIGNER.0.iter(),
+            INIT_IX_IS_WRITER.0.iter(),
+        ),
+    )
+}
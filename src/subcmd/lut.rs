@@ -0,0 +1,121 @@
+use clap::{Args, builder::ValueParser};
+use inf1_pp_flatslab_core::{accounts::Slab, keys::SLAB_ID};
+use solana_commitment_config::CommitmentConfig;
+use solana_pubkey::Pubkey;
+use solana_signer::Signer;
+
+use crate::{
+    confirm::SendSummary,
+    err::{CliErr, CliResult},
+    lut::{MAX_EXTEND_ADDRESSES, create_lut_ix, extend_lut_ix, fetch_lut},
+    rpc::Rpc,
+    sscu::{parse_pubkey_from_src, parse_signer},
+    utils::{TxCtxSrc, fetch_slab_data},
+};
+
+#[derive(Args, Debug)]
+#[command(
+    long_about = "Creates or extends an address lookup table with the accounts used by admin transactions, for use with --lut. The config wallet is the table's authority and payer. Signers such as the admin and invoked programs cannot be looked up so are not added."
+)]
+pub struct LutArgs {
+    #[arg(
+        long,
+        help = "Existing lookup table to extend. A new one is created if not set.",
+        value_parser = ValueParser::new(parse_pubkey_from_src)
+    )]
+    pub address: Option<Pubkey>,
+
+    #[arg(
+        long,
+        help = "Also add the mints of all entries currently on the slab",
+        default_value_t = false
+    )]
+    pub include_mints: bool,
+}
+
+impl LutArgs {
+    pub async fn run(&self, args: &crate::Args, rpc: &dyn Rpc) -> CliResult<()> {
+        let crate::Args {
+            config,
+            send_mode,
+            yes,
+            nonce,
+            slab_src,
+            ..
+        } = args;
+        slab_src.reject("lut")?;
+
+        let Self {
+            address,
+            include_mints,
+        } = self;
+        // the config wallet creates and extends the table itself,
+        // and transactions cannot be compiled with a table that may not exist yet
+        let src = TxCtxSrc {
+            proposer: None,
+            nonce: nonce.to_nonce(config)?,
+            lut: None,
+            payer: parse_signer(&config.keypair_path).map_err(CliErr::Config)?,
+            admin: None,
+        };
+        let ctx = src.ctx(args, rpc);
+        let authority = src.payer.as_ref();
+        let authority_pk = authority.pubkey();
+
+        let mut addresses = vec![
+            Pubkey::new_from_array(SLAB_ID),
+            Pubkey::default(), // system program
+        ];
+        if *include_mints {
+            let slab_d = fetch_slab_data(rpc).await?;
+            let slab = Slab::of_acc_data(&slab_d).unwrap();
+            addresses.extend(
+                slab.entries()
+                    .0
+                    .iter()
+                    .map(|e| Pubkey::new_from_array(*e.mint())),
+            );
+        }
+
+        let mut ixs = vec![];
+        let lut = match address {
+            Some(address) => {
+                let existing = fetch_lut(rpc, address).await?;
+                addresses.retain(|a| !existing.addresses.contains(a));
+                *address
+            }
+            None => {
+                let recent_slot = rpc
+                    .get_slot_with_commitment(CommitmentConfig::finalized())
+                    .await?;
+                let (ix, lut) = create_lut_ix(&authority_pk, &authority_pk, recent_slot);
+                eprintln!("Creating lookup table {lut}");
+                ixs.push(vec![ix]);
+                lut
+            }
+        };
+        ixs.extend(
+            addresses
+                .chunks(MAX_EXTEND_ADDRESSES)
+                .map(|chunk| vec![extend_lut_ix(&lut, &authority_pk, &authority_pk, chunk)]),
+        );
+
+        if ixs.is_empty() {
+            eprintln!("All addresses already in lookup table");
+        } else {
+            eprintln!("Adding {} addresses to lookup table", addresses.len());
+            SendSummary {
+                rpc,
+                signers: &ctx.signer_pks(&[authority_pk]),
+                n_txs: ixs.len(),
+            }
+            .confirm(*send_mode, *yes)?;
+
+            for ixs in ixs {
+                ctx.send(ixs, vec![authority]).await?;
+            }
+        }
+        println!("{lut}");
+        Ok(())
+    }
+}
@@ -1,33 +1,68 @@
 use clap::Subcommand;
 
-use crate::subcmd::{
-    init::InitArgs, set_admin::SetAdminArgs, sync_prices::SyncPricesArgs,
-    view_admin::ViewAdminArgs, view_prices::ViewPricesArgs,
+use crate::{
+    err::CliResult,
+    rpc::Rpc,
+    subcmd::{
+        apply_plan::ApplyPlanArgs, broadcast::BroadcastArgs, diff::DiffArgs, init::InitArgs,
+        lut::LutArgs, quote::QuoteArgs, restore::RestoreArgs, set_admin::SetAdminArgs,
+        sign::SignArgs, snapshot::SnapshotArgs, sync_prices::SyncPricesArgs,
+        view_admin::ViewAdminArgs, view_prices::ViewPricesArgs,
+    },
 };
 
+mod apply_plan;
+mod broadcast;
+mod diff;
+#[cfg(all(test, feature = "e2e"))]
+mod e2e;
 mod init;
+mod lut;
+mod quote;
+mod restore;
 mod set_admin;
+mod sign;
+mod snapshot;
 mod sync_prices;
 mod view_admin;
 mod view_prices;
 
+#[cfg(test)]
+pub use set_admin::set_admin_ix;
+
 #[derive(Debug, Subcommand)]
 pub enum Subcmd {
+    ApplyPlan(ApplyPlanArgs),
+    Broadcast(BroadcastArgs),
+    Diff(DiffArgs),
     Init(InitArgs),
+    Lut(LutArgs),
+    Quote(QuoteArgs),
+    Restore(RestoreArgs),
     SetAdmin(SetAdminArgs),
+    Sign(SignArgs),
+    Snapshot(SnapshotArgs),
     SyncPrices(SyncPricesArgs),
     ViewAdmin(ViewAdminArgs),
     ViewPrices(ViewPricesArgs),
 }
 
 impl Subcmd {
-    pub async fn run(args: crate::Args) {
+    pub async fn run(args: crate::Args, rpc: &dyn Rpc) -> CliResult<()> {
         match &args.subcmd {
-            Self::Init(a) => a.run(&args).await,
-            Self::SetAdmin(a) => a.run(&args).await,
-            Self::SyncPrices(a) => a.run(&args).await,
-            Self::ViewAdmin(a) => a.run(&args).await,
-            Self::ViewPrices(a) => a.run(&args).await,
+            Self::ApplyPlan(a) => a.run(&args, rpc).await,
+            Self::Broadcast(a) => a.run(&args, rpc).await,
+            Self::Diff(a) => a.run(&args, rpc).await,
+            Self::Init(a) => a.run(&args, rpc).await,
+            Self::Lut(a) => a.run(&args, rpc).await,
+            Self::Quote(a) => a.run(&args, rpc).await,
+            Self::Restore(a) => a.run(&args, rpc).await,
+            Self::SetAdmin(a) => a.run(&args, rpc).await,
+            Self::Sign(a) => a.run(&args, rpc).await,
+            Self::Snapshot(a) => a.run(&args, rpc).await,
+            Self::SyncPrices(a) => a.run(&args, rpc).await,
+            Self::ViewAdmin(a) => a.run(&args, rpc).await,
+            Self::ViewPrices(a) => a.run(&args, rpc).await,
         }
     }
 }
@@ -0,0 +1,71 @@
+use clap::{Args, builder::ValueParser};
+use inf1_pp_flatslab_core::accounts::Slab;
+use solana_pubkey::Pubkey;
+
+use crate::{
+    err::CliResult,
+    quote::{Quote, QuoteDir},
+    rpc::Rpc,
+    sscu::parse_pubkey_from_src,
+};
+
+#[derive(Args, Debug)]
+#[command(
+    long_about = "Outputs the fee the slab currently charges for swapping one LST for another. Amounts are in lamports of SOL value."
+)]
+pub struct QuoteArgs {
+    #[arg(
+        long,
+        help = "Mint of the LST going in",
+        value_parser = ValueParser::new(parse_pubkey_from_src)
+    )]
+    pub inp: Pubkey,
+
+    #[arg(
+        long,
+        help = "Mint of the LST coming out",
+        value_parser = ValueParser::new(parse_pubkey_from_src)
+    )]
+    pub out: Pubkey,
+
+    #[arg(
+        long,
+        help = "SOL value of the input to quote the output for, or of the output to quote the input for if --exact-out is set"
+    )]
+    pub amount: Option<u64>,
+
+    #[arg(
+        long,
+        help = "If flag set, --amount is the SOL value coming out of the swap",
+        default_value_t = false,
+        requires = "amount"
+    )]
+    pub exact_out: bool,
+}
+
+impl QuoteArgs {
+    pub async fn run(
+        &self,
+        crate::Args { slab_src, .. }: &crate::Args,
+        rpc: &dyn Rpc,
+    ) -> CliResult<()> {
+        let Self {
+            inp,
+            out,
+            amount,
+            exact_out,
+        } = self;
+
+        let slab_d = slab_src.fetch(rpc).await?;
+        let slab = Slab::of_acc_data(&slab_d).unwrap();
+
+        let dir = if *exact_out {
+            QuoteDir::ExactOut
+        } else {
+            QuoteDir::ExactIn
+        };
+        let quote = Quote::new(&slab, inp, out, amount.map(|a| (dir, a)))?;
+        print!("{quote}");
+        Ok(())
+    }
+}
@@ -0,0 +1,69 @@
+use std::sync::Arc;
+
+use clap::Args;
+use inf1_pp_flatslab_core::accounts::Slab;
+use solana_pubkey::Pubkey;
+
+use crate::{
+    err::CliResult,
+    rpc::Rpc,
+    snapshot::read_slab_snapshot_file,
+    syncplan::SyncExec,
+    utils::{TxCtxSrc, fetch_slab_data},
+};
+
+#[derive(Args, Debug)]
+#[command(
+    long_about = "Sets and removes entries to bring the slab onchain back to the state saved by snapshot. Does not change the admin."
+)]
+pub struct RestoreArgs {
+    #[arg(
+        long,
+        short,
+        help = "Path to admin keypair signer. Defaults to config wallet if not set."
+    )]
+    pub admin: Option<Arc<str>>,
+
+    #[arg(help = "Path to slab snapshot json file")]
+    pub snapshot: Arc<str>,
+}
+
+impl RestoreArgs {
+    pub async fn run(&self, args: &crate::Args, rpc: &dyn Rpc) -> CliResult<()> {
+        let crate::Args { yes, slab_src, .. } = args;
+        slab_src.reject("restore")?;
+
+        let Self { admin, snapshot } = self;
+
+        let snapshot = read_slab_snapshot_file(snapshot.as_ref())?;
+
+        let src = TxCtxSrc::from_args(args, admin.as_deref(), rpc).await?;
+
+        let slab_d = fetch_slab_data(rpc).await?;
+        let slab = Slab::of_acc_data(&slab_d).unwrap();
+
+        let curr_admin = Pubkey::new_from_array(*slab.admin());
+        if curr_admin != snapshot.admin {
+            eprintln!(
+                "Warning: current admin {curr_admin} differs from snapshot's {}. Use set-admin to restore it.",
+                snapshot.admin
+            );
+        }
+
+        let plan = snapshot.restore_plan(&slab_d);
+        if plan.ops.is_empty() {
+            eprintln!("Slab entries already match snapshot");
+            return Ok(());
+        }
+
+        SyncExec {
+            slab_d: &slab_d,
+            payer: src.payer.as_ref(),
+            admin: src.admin(),
+            ctx: src.ctx(args, rpc),
+            yes: *yes,
+        }
+        .exec(&plan.ops)
+        .await
+    }
+}
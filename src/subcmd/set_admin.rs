@@ -10,14 +10,14 @@ use inf1_pp_flatslab_core::{
 };
 use solana_instruction::Instruction;
 use solana_pubkey::Pubkey;
-use solana_rpc_client::nonblocking::rpc_client::RpcClient;
 use solana_signer::Signer;
 
 use crate::{
-    sscu::{parse_pubkey_from_src, parse_signer},
-    utils::{
-        fetch_slab_data, handle_tx, keys_signer_writable_to_metas, to_signed_tx, with_auto_cb,
-    },
+    confirm::SendSummary,
+    err::CliResult,
+    rpc::Rpc,
+    sscu::parse_pubkey_from_src,
+    utils::{TxCtxSrc, fetch_slab_data, keys_signer_writable_to_metas},
 };
 
 #[derive(Args, Debug)]
@@ -37,43 +37,98 @@ pub struct SetAdminArgs {
 }
 
 impl SetAdminArgs {
-    pub async fn run(
-        &self,
-        crate::Args {
-            config,
+    pub async fn run(&self, args: &crate::Args, rpc: &dyn Rpc) -> CliResult<()> {
+        let crate::Args {
             send_mode,
-            fee_cb,
+            yes,
+            slab_src,
             ..
-        }: &crate::Args,
-    ) {
+        } = args;
+        slab_src.reject("set-admin")?;
+
         let Self { admin, new_admin } = self;
-        let rpc = RpcClient::new(config.json_rpc_url.to_owned());
-        let payer = parse_signer(&config.keypair_path).unwrap();
+        let src = TxCtxSrc::from_args(args, admin.as_deref(), rpc).await?;
+        let ctx = src.ctx(args, rpc);
+        let payer = src.payer.as_ref();
         let payer_pk = payer.pubkey();
-        let admin_opt = admin.as_ref().map(|s| parse_signer(s).unwrap());
-        let admin = admin_opt.as_ref().unwrap_or(&payer);
+        let admin = src.admin();
 
-        let slab_d = fetch_slab_data(&rpc).await;
+        let slab_d = fetch_slab_data(rpc).await?;
         let slab = Slab::of_acc_data(&slab_d).unwrap();
 
-        let set_admin_ix = Instruction::new_with_bytes(
-            crate::PROGRAM_ID.into(),
-            SetAdminIxData::new().as_buf(),
-            keys_signer_writable_to_metas(
-                NewSetAdminIxAccsBuilder::start()
-                    .with_current_admin(*slab.admin())
-                    .with_new_admin(new_admin.to_bytes())
-                    .with_slab(SLAB_ID)
-                    .build()
-                    .0
-                    .iter(),
-                SET_ADMIN_IX_IS_SIGNER.0.iter(),
-                SET_ADMIN_IX_IS_WRITER.0.iter(),
-            ),
+        let set_admin_ix = set_admin_ix(slab.admin(), new_admin);
+
+        eprintln!(
+            "Setting admin: {} -> {new_admin}",
+            Pubkey::new_from_array(*slab.admin())
         );
+        SendSummary {
+            rpc,
+            signers: &ctx.signer_pks(&[payer_pk, admin.pubkey()]),
+            n_txs: 1,
+        }
+        .confirm(*send_mode, *yes)?;
+
+        ctx.send(vec![set_admin_ix], vec![payer, admin]).await
+    }
+}
+
+pub fn set_admin_ix(curr_admin: &[u8; 32], new_admin: &Pubkey) -> Instruction {
+    Instruction::new_with_bytes(
+        crate::PROGRAM_ID.into(),
+        SetAdminIxData::new().as_buf(),
+        keys_signer_writable_to_metas(
+            NewSetAdminIxAccsBuilder::start()
+                .with_current_admin(*curr_admin)
+                .with_new_admin(new_admin.to_bytes())
+                .with_slab(SLAB_ID)
+                .build()
+                .0
+                .iter(),
+            SET_ADMIN_IX_IS_SIGNER.0.iter(),
+            SET_ADMIN_IX_IS_WRITER.0.iter(),
+        ),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{err::CliErr, rpc::mock::MockRpc, subcmd::Subcmd, testcfg::TestCfg};
+
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn run_sends_set_admin() {
+        let cfg = TestCfg::new();
+        let wallet = cfg.wallet.pubkey();
+        let rpc = MockRpc {
+            balance: 1_000_000_000_000,
+            ..Default::default()
+        }
+        .with_slab(&wallet, &[]);
+        let new_admin = Pubkey::new_unique();
+
+        Subcmd::run(cfg.args(&["set-admin", &new_admin.to_string()]), &rpc)
+            .await
+            .unwrap();
+        let sent = rpc.sent();
+        assert_eq!(sent.len(), 1);
+        let keys = sent[0].message.static_account_keys();
+        assert_eq!(keys[0], wallet);
+        assert!(keys.contains(&new_admin));
 
-        let ixs = with_auto_cb(vec![set_admin_ix], &payer_pk, &rpc, *send_mode, *fee_cb).await;
-        let tx = to_signed_tx(ixs, vec![&payer, &admin], &rpc).await;
-        handle_tx(&rpc, *send_mode, &tx).await;
+        let slab_file = cfg.write("slab.bin", crate::rpc::mock::mock_slab_data(&wallet, &[]));
+        let res = Subcmd::run(
+            cfg.args(&[
+                "--slab-file",
+                &slab_file,
+                "set-admin",
+                &new_admin.to_string(),
+            ]),
+            &rpc,
+        )
+        .await;
+        assert!(matches!(res, Err(CliErr::Input(_))));
+        assert_eq!(rpc.sent().len(), 1);
     }
 }
@@ -0,0 +1,47 @@
+use std::sync::Arc;
+
+use clap::Args;
+
+use crate::{
+    err::{CliErr, CliResult},
+    rpc::Rpc,
+    sscu::parse_signer,
+    txser::{invalid_signers, parse_tx_arg, sign_tx},
+};
+
+#[derive(Args, Debug)]
+#[command(
+    long_about = "Adds signatures to a transaction output by --send-mode dump64 or dump58 and outputs it in the same encoding. Does not require network access."
+)]
+pub struct SignArgs {
+    #[arg(
+        long,
+        short,
+        help = "Path to keypair signer to sign with. Can be specified multiple times.",
+        required = true
+    )]
+    pub signer: Vec<Arc<str>>,
+
+    #[arg(help = "Base64 or base58 serialized transaction, or path to a file containing one")]
+    pub tx: Arc<str>,
+}
+
+impl SignArgs {
+    pub async fn run(&self, _args: &crate::Args, _rpc: &dyn Rpc) -> CliResult<()> {
+        let Self { signer, tx } = self;
+
+        let (mut tx, enc) = parse_tx_arg(tx).map_err(CliErr::Input)?;
+        for s in signer.iter() {
+            let s = parse_signer(s).map_err(CliErr::Config)?;
+            sign_tx(&mut tx, s.as_ref()).map_err(CliErr::Input)?;
+        }
+
+        let missing = invalid_signers(&tx);
+        if !missing.is_empty() {
+            eprintln!("Still missing signatures from:");
+            missing.iter().for_each(|pk| eprintln!("{pk}"));
+        }
+        println!("{}", enc.encode(&tx));
+        Ok(())
+    }
+}
@@ -0,0 +1,58 @@
+use std::{
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use clap::Args;
+
+use crate::{
+    err::CliResult,
+    rpc::Rpc,
+    snapshot::{SlabSnapshot, write_slab_snapshot_file},
+    utils::fetch_slab_data_with_slot,
+};
+
+#[derive(Args, Debug)]
+#[command(
+    long_about = "Saves the slab's current admin and entries to a json file for use with restore"
+)]
+pub struct SnapshotArgs {
+    #[arg(
+        long,
+        short,
+        help = "Path to write the snapshot to. Defaults to slab-snapshot-{unix timestamp}.json"
+    )]
+    pub out: Option<Arc<str>>,
+}
+
+impl SnapshotArgs {
+    pub async fn run(
+        &self,
+        crate::Args { slab_src, .. }: &crate::Args,
+        rpc: &dyn Rpc,
+    ) -> CliResult<()> {
+        slab_src.reject("snapshot")?;
+
+        let Self { out } = self;
+
+        let (slot, slab_d) = fetch_slab_data_with_slot(rpc, rpc.commitment()).await?;
+        let snapshot = SlabSnapshot::new(slot, &slab_d);
+
+        let out = out.as_ref().map_or_else(
+            || {
+                let ts = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap()
+                    .as_secs();
+                format!("slab-snapshot-{ts}.json")
+            },
+            |o| o.to_string(),
+        );
+        write_slab_snapshot_file(&out, &snapshot)?;
+        eprintln!(
+            "Wrote snapshot of {} entries at slot {slot} to {out}",
+            snapshot.entries.len()
+        );
+        Ok(())
+    }
+}
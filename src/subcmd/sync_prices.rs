@@ -1,35 +1,25 @@
-use std::{process::exit, sync::Arc};
-
-use clap::Args;
-use inf1_pp_flatslab_core::{
-    accounts::Slab,
-    instructions::admin::{
-        remove_lst::{
-            NewRemoveLstIxAccsBuilder, REMOVE_LST_IX_IS_SIGNER, REMOVE_LST_IX_IS_WRITER,
-            RemoveLstIxData,
-        },
-        set_lst_fee::{
-            NewSetLstFeeIxAccsBuilder, SET_LST_FEE_IX_IS_SIGNER, SET_LST_FEE_IX_IS_WRITER,
-            SetLstFeeIxArgs, SetLstFeeIxData,
-        },
-    },
-    keys::SLAB_ID,
-};
-use solana_instruction::Instruction;
-use solana_pubkey::Pubkey;
-use solana_rpc_client::nonblocking::rpc_client::RpcClient;
+use std::{path::Path, sync::Arc, time::Duration};
+
+use clap::{Args, ValueEnum};
+use inf1_pp_flatslab_core::accounts::Slab;
+use solana_commitment_config::CommitmentConfig;
+use tokio::time::{Instant, sleep};
 
 use crate::{
-    slabcsv::{SlabCsvEntry, read_slab_csv_file},
-    sscu::parse_signer,
-    utils::{
-        fetch_slab_data, handle_tx, keys_signer_writable_to_metas, to_signed_tx, with_auto_cb,
+    err::{CliErr, CliResult},
+    mintfilter::MintFilter,
+    rpc::Rpc,
+    slabcsv::{SlabCsvEntry, read_sorted_slab_csv_file},
+    slabdiff::SlabDiff,
+    sscu::TxSendMode,
+    syncplan::{
+        SyncExec, SyncJournal, SyncPlan, op_entry_sets, project_entries, read_sync_journal_file,
+        write_sync_journal_file, write_sync_plan_file,
     },
+    utils::{TxCtxSrc, fetch_slab_data_with_slot},
+    verify::VerifyArgs,
 };
 
-const MAX_SET_LST_FEE_IX_PER_TX: usize = 17;
-const MAX_REMOVE_LST_IX_PER_TX: usize = 18;
-
 #[derive(Args, Debug)]
 #[command(long_about = "Syncs price entries with the slab onchain")]
 pub struct SyncPricesArgs {
@@ -43,11 +33,20 @@ pub struct SyncPricesArgs {
 
     #[arg(
         long,
-        short,
-        help = "If flag set, skip verification step that verifies that no (inp, out) pair results in a negative fee before running the operation.",
-        default_value_t = false
+        value_delimiter = ',',
+        help = "Only sync these mints. Comma-separated list of mints or paths to files of one mint per line."
     )]
-    pub no_verify: bool,
+    pub only: Vec<Arc<str>>,
+
+    #[arg(
+        long,
+        value_delimiter = ',',
+        help = "Never sync these mints. Comma-separated list of mints or paths to files of one mint per line."
+    )]
+    pub exclude: Vec<Arc<str>>,
+
+    #[command(flatten)]
+    pub verify: VerifyArgs,
 
     #[arg(
         long,
@@ -56,270 +55,386 @@ pub struct SyncPricesArgs {
     )]
     pub admin: Option<Arc<str>>,
 
+    #[arg(
+        long,
+        help = "If set, writes the sync plan to this path for review and later use with apply-plan instead of running it"
+    )]
+    pub plan_out: Option<Arc<str>>,
+
+    #[arg(
+        long,
+        help = "Path to journal file to record the progress of each transaction to. Only used with --send-mode send-actual."
+    )]
+    pub journal: Option<Arc<str>>,
+
+    #[arg(
+        long,
+        requires = "journal",
+        help = "If flag set, resumes the interrupted sync recorded in --journal instead of computing a new one from the csv. Its remaining ops are verified against the slab's current state.",
+        default_value_t = false
+    )]
+    pub resume: bool,
+
+    #[arg(
+        long,
+        help = "If flag set, skips refetching the slab after sending to check that it matches the expected state",
+        default_value_t = false
+    )]
+    pub no_reconcile: bool,
+
+    #[arg(
+        long,
+        help = "Commitment to refetch the slab at for reconciliation",
+        default_value_t = ReconcileCommitment::default(),
+        value_enum
+    )]
+    pub reconcile_commitment: ReconcileCommitment,
+
     #[arg(help = "Path to slab prices csv file", default_value_t = {"slab.csv".into()})]
     pub csv: Arc<str>,
 }
 
 impl SyncPricesArgs {
-    pub async fn run(
-        &self,
-        crate::Args {
-            config,
+    pub async fn run(&self, args: &crate::Args, rpc: &dyn Rpc) -> CliResult<()> {
+        let crate::Args {
             send_mode,
-            fee_cb,
+            yes,
+            slab_src,
             ..
-        }: &crate::Args,
-    ) {
+        } = args;
         let Self {
             remove,
-            no_verify,
+            verify,
             csv,
             admin,
+            plan_out,
+            journal,
+            resume,
+            no_reconcile,
+            reconcile_commitment,
+            only,
+            exclude,
         } = self;
 
-        let mut entries = read_slab_csv_file(csv.as_ref());
-        entries.sort_unstable_by_key(|e| e.mint);
-
-        entries.as_slice().windows(2).for_each(|s| {
-            if s[0].mint == s[1].mint {
-                panic!("Duplicate mint {}", s[0].mint);
+        // in proposer modes a confirmed batch is only a created proposal,
+        // so the slab cannot be checked against the journal on resume
+        let journal = match (journal, send_mode) {
+            (Some(j), TxSendMode::SendActual) => Some(Path::new(j.as_ref())),
+            (Some(_), _) => {
+                return Err(CliErr::Input(
+                    "--journal is only supported with --send-mode send-actual".to_owned(),
+                ));
             }
-        });
+            (None, _) => None,
+        };
 
-        let rpc = RpcClient::new(config.json_rpc_url.to_owned());
-        let payer = parse_signer(&config.keypair_path).unwrap();
-        let payer_pk = payer.pubkey();
-        let admin_opt = admin.as_ref().map(|s| parse_signer(s).unwrap());
-        let admin = admin_opt.as_ref().unwrap_or(&payer);
-
-        if !*no_verify {
-            let inp_key = |e: &&SlabCsvEntry| e.inp;
-            let out_key = |e: &&SlabCsvEntry| e.out;
+        if slab_src.slab_file.is_some() && plan_out.is_none() {
+            return Err(CliErr::Input(
+                "--slab-file can only be used with --plan-out".to_owned(),
+            ));
+        }
 
-            let [s_inp, s_out] = [inp_key, out_key].map(|key_fn| entries.iter().min_by_key(key_fn));
+        let slab_d = slab_src.fetch(rpc).await?;
+        let slab = Slab::of_acc_data(&slab_d).unwrap();
 
-            match (s_inp, s_out) {
-                (Some(s_inp), Some(s_out)) => {
-                    if s_inp.inp + s_out.out < 0 {
-                        eprintln!("inp={}, out={} results in fee < 0", s_inp.mint, s_out.mint);
-                        exit(-1);
-                    }
+        // planned before any signer is parsed so that --plan-out works offline without a wallet
+        let new_plan = if *resume {
+            None
+        } else {
+            let entries = read_sorted_slab_csv_file(csv.as_ref())?;
+            let mut diff = SlabDiff::new(&entries, &slab, *remove);
+            MintFilter::from_args(only, exclude)?.apply(&mut diff);
+            let plan = SyncPlan::new(&slab_d, &diff);
+
+            let projected = project_entries(
+                slab.entries().0.iter().map(|e| SlabCsvEntry::from(*e)),
+                &plan.ops,
+            );
+
+            verify.check(&projected, &diff.sets)?;
+
+            if let Some(plan_out) = plan_out {
+                write_sync_plan_file(plan_out.as_ref(), &plan)?;
+                eprintln!("Wrote {} ops to {plan_out}", plan.ops.len());
+                return Ok(());
+            }
+            Some((plan, projected))
+        };
+
+        let src = TxCtxSrc::from_args(args, admin.as_deref(), rpc).await?;
+        let exec = SyncExec {
+            slab_d: &slab_d,
+            payer: src.payer.as_ref(),
+            admin: src.admin(),
+            ctx: src.ctx(args, rpc),
+            yes: *yes,
+        };
+
+        let projected = match new_plan {
+            None => {
+                // clap `requires` ensures journal is set
+                let journal = journal.unwrap();
+                let mut j = read_sync_journal_file(journal)?;
+                exec.resolve_sent(&mut j, journal).await?;
+
+                // refetch at the same commitment sent batches were resolved at
+                // so that every confirmed batch is reflected
+                let (_, slab_d) =
+                    fetch_slab_data_with_slot(rpc, CommitmentConfig::confirmed()).await?;
+                let slab = Slab::of_acc_data(&slab_d).unwrap();
+                j.check_applied(&slab)?;
+
+                let remaining = j.remaining().concat();
+                let projected = project_entries(
+                    slab.entries().0.iter().map(|e| SlabCsvEntry::from(*e)),
+                    &remaining,
+                );
+                verify.check(&projected, &op_entry_sets(&slab, &remaining))?;
+
+                SyncExec {
+                    slab_d: &slab_d,
+                    ..exec
                 }
-                _empty_list => (),
+                .exec_journaled(j, journal)
+                .await?;
+                projected
             }
-        }
-
-        let slab_d = fetch_slab_data(&rpc).await;
-        let slab = Slab::of_acc_data(&slab_d).unwrap();
-        let curr_entries = slab.entries();
-
-        let (msg, ixs): (String, Vec<Instruction>) =
-            entries
-                .iter()
-                .fold((String::new(), vec![]), |(mut msg, mut ixs), e| {
-                    if let Ok(curr) = curr_entries.find_by_mint(e.mint.as_array()) {
-                        if curr.inp_fee_nanos() == e.inp && curr.out_fee_nanos() == e.out {
-                            return (msg, ixs);
+            Some((plan, projected)) => {
+                match journal {
+                    Some(journal) => {
+                        if journal.exists() {
+                            return Err(CliErr::Input(format!(
+                                "Journal {} already exists. Use --resume to continue it or delete it to start a new sync",
+                                journal.display()
+                            )));
                         }
+                        let j = SyncJournal::new(exec.pack(&plan.ops)?);
+                        write_sync_journal_file(journal, &j)?;
+                        exec.exec_journaled(j, journal).await?;
                     }
-
-                    msg.extend(setting_msg(e).drain(..));
-                    msg.push('\n');
-
-                    let set_lst_ix = Instruction::new_with_bytes(
-                        crate::PROGRAM_ID.into(),
-                        SetLstFeeIxData::new(SetLstFeeIxArgs {
-                            inp_fee_nanos: e.inp,
-                            out_fee_nanos: e.out,
-                        })
-                        .as_buf(),
-                        keys_signer_writable_to_metas(
-                            NewSetLstFeeIxAccsBuilder::start()
-                                .with_admin(slab.admin())
-                                .with_mint(e.mint.as_array())
-                                .with_payer(payer_pk.as_array())
-                                .with_slab(&SLAB_ID)
-                                .with_system_program(&[0u8; 32])
-                                .build()
-                                .0
-                                .iter()
-                                .copied(),
-                            SET_LST_FEE_IX_IS_SIGNER.0.iter(),
-                            SET_LST_FEE_IX_IS_WRITER.0.iter(),
-                        ),
-                    );
-                    ixs.push(set_lst_ix);
-
-                    (msg, ixs)
-                });
-
-        if !msg.is_empty() {
-            eprintln!("Setting:");
-            eprint!("{msg}");
+                    None => exec.exec(&plan.ops).await?,
+                }
+                projected
+            }
+        };
+
+        if *send_mode == TxSendMode::SendActual
+            && !*no_reconcile
+            && !reconcile(rpc, &projected, (*reconcile_commitment).into()).await?
+        {
+            return Err(CliErr::Verify(
+                "Slab did not reconcile to the expected state".to_owned(),
+            ));
         }
+        Ok(())
+    }
+}
 
-        for batch in ixs.chunks(MAX_SET_LST_FEE_IX_PER_TX) {
-            let ixs = with_auto_cb(batch.into(), &payer_pk, &rpc, *send_mode, *fee_cb).await;
-            let tx = to_signed_tx(ixs, vec![&payer, admin], &rpc).await;
-            handle_tx(&rpc, *send_mode, &tx).await;
-        }
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, ValueEnum)]
+pub enum ReconcileCommitment {
+    #[default]
+    Confirmed,
+    Finalized,
+}
 
-        if !*remove {
-            return;
+impl From<ReconcileCommitment> for CommitmentConfig {
+    fn from(value: ReconcileCommitment) -> Self {
+        match value {
+            ReconcileCommitment::Confirmed => Self::confirmed(),
+            ReconcileCommitment::Finalized => Self::finalized(),
         }
+    }
+}
 
-        // Remove
-
-        let (msg, ixs): (String, Vec<Instruction>) =
-            curr_entries
-                .0
-                .iter()
-                .fold((String::new(), vec![]), |(mut msg, mut ixs), curr| {
-                    if entries.iter().any(|e| e.mint.as_array() == curr.mint()) {
-                        return (msg, ixs);
-                    }
-
-                    msg.extend(Pubkey::new_from_array(*curr.mint()).to_string().drain(..));
-                    msg.push('\n');
-
-                    let remove_ix = Instruction::new_with_bytes(
-                        crate::PROGRAM_ID.into(),
-                        RemoveLstIxData::new().as_buf(),
-                        keys_signer_writable_to_metas(
-                            NewRemoveLstIxAccsBuilder::start()
-                                .with_admin(slab.admin())
-                                .with_mint(curr.mint())
-                                .with_refund_rent_to(payer_pk.as_array())
-                                .with_slab(&SLAB_ID)
-                                .build()
-                                .0
-                                .iter()
-                                .copied(),
-                            REMOVE_LST_IX_IS_SIGNER.0.iter(),
-                            REMOVE_LST_IX_IS_WRITER.0.iter(),
-                        ),
-                    );
-                    ixs.push(remove_ix);
-
-                    (msg, ixs)
-                });
-
-        if !msg.is_empty() {
-            eprintln!("Removing:");
-            eprint!("{msg}");
+const RECONCILE_TIMEOUT: Duration = Duration::from_secs(60);
+const RECONCILE_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Refetches the slab until its entries match `expected` or [`RECONCILE_TIMEOUT`] elapses,
+/// since the sync's transactions may not have reached `commitment` yet.
+///
+/// Prints the reconciliation report and returns whether the slab matches.
+async fn reconcile(
+    rpc: &dyn Rpc,
+    expected: &[SlabCsvEntry],
+    commitment: CommitmentConfig,
+) -> CliResult<bool> {
+    let start = Instant::now();
+    loop {
+        let (slot, slab_d) = fetch_slab_data_with_slot(rpc, commitment).await?;
+        let slab = Slab::of_acc_data(&slab_d).unwrap();
+        let drift = SlabDiff::new(expected, &slab, true);
+        if drift.is_empty() {
+            eprintln!(
+                "Reconciled: all {} slab entries match at slot {slot}",
+                expected.len()
+            );
+            return Ok(true);
         }
-
-        for batch in ixs.chunks(MAX_REMOVE_LST_IX_PER_TX) {
-            let ixs = with_auto_cb(batch.into(), &payer_pk, &rpc, *send_mode, *fee_cb).await;
-            let tx = to_signed_tx(ixs, vec![&payer, admin], &rpc).await;
-            handle_tx(&rpc, *send_mode, &tx).await;
+        if start.elapsed() >= RECONCILE_TIMEOUT {
+            eprintln!(
+                "Slab at slot {slot} has drifted from the expected state. Changes required to reconcile:"
+            );
+            eprint!("{drift}");
+            return Ok(false);
         }
+        sleep(RECONCILE_POLL_INTERVAL).await;
     }
 }
 
-fn setting_msg(entry: &SlabCsvEntry) -> String {
-    format!("{} inp={} out={}", entry.mint, entry.inp, entry.out)
-}
-
 #[cfg(test)]
 mod tests {
-    use solana_compute_budget_interface::ComputeBudgetInstruction;
-    use solana_message::{VersionedMessage, v0::Message};
-    use solana_signature::Signature;
-    use solana_transaction::versioned::VersionedTransaction;
+    use solana_pubkey::Pubkey;
+    use solana_signer::Signer;
+
+    use crate::{rpc::mock::MockRpc, subcmd::Subcmd, testcfg::TestCfg};
 
     use super::*;
 
-    pub fn to_mocked_tx(ixs: Vec<Instruction>, payer_pk: &Pubkey) -> VersionedTransaction {
-        let message = VersionedMessage::V0(
-            Message::try_compile(payer_pk, &ixs, &[], Default::default()).unwrap(),
-        );
-        VersionedTransaction {
-            signatures: vec![Signature::default(); message.header().num_required_signatures.into()],
-            message,
-        }
+    fn entry(mint: Pubkey, inp: i32) -> SlabCsvEntry {
+        SlabCsvEntry { mint, inp, out: 0 }
     }
 
-    #[test]
-    fn verify_max_set_lst_fee_ix_per_tx() {
-        let payer = Pubkey::new_unique();
+    #[tokio::test(start_paused = true)]
+    async fn reconcile_cases() {
         let admin = Pubkey::new_unique();
-        let ixs: Vec<_> = [
-            ComputeBudgetInstruction::set_compute_unit_limit(0),
-            ComputeBudgetInstruction::set_compute_unit_price(0),
-        ]
-        .into_iter()
-        .chain((0..MAX_SET_LST_FEE_IX_PER_TX).map(|_| {
-            Instruction::new_with_bytes(
-                crate::PROGRAM_ID.into(),
-                SetLstFeeIxData::new(SetLstFeeIxArgs {
-                    inp_fee_nanos: 0,
-                    out_fee_nanos: 0,
-                })
-                .as_buf(),
-                keys_signer_writable_to_metas(
-                    NewSetLstFeeIxAccsBuilder::start()
-                        .with_admin(admin.as_array())
-                        .with_mint(Pubkey::new_unique().as_array())
-                        .with_payer(payer.as_array())
-                        .with_slab(&SLAB_ID)
-                        .with_system_program(&[0u8; 32])
-                        .build()
-                        .0
-                        .iter()
-                        .copied(),
-                    SET_LST_FEE_IX_IS_SIGNER.0.iter(),
-                    SET_LST_FEE_IX_IS_WRITER.0.iter(),
-                ),
-            )
-        }))
-        .collect();
-
-        let tx = to_mocked_tx(ixs, &payer);
-
-        let tx_byte_len = bincode::serialize(&tx).unwrap().len();
-        //eprintln!("{tx_byte_len}");
-        assert!(tx_byte_len < 1232, "{tx_byte_len}");
+        let [m0, m1] = core::array::from_fn(|_| Pubkey::new_unique());
+        let expected = [entry(m0, 1)];
+        let run = async |onchain: &[SlabCsvEntry]| {
+            let rpc = MockRpc::default().with_slab(&admin, onchain);
+            let start = Instant::now();
+            let res = reconcile(&rpc, &expected, CommitmentConfig::confirmed())
+                .await
+                .unwrap();
+            (res, start.elapsed())
+        };
+
+        let (matched, elapsed) = run(&expected).await;
+        assert!(matched);
+        assert_eq!(elapsed, Duration::ZERO);
+
+        let (matched, elapsed) = run(&[entry(m0, 2)]).await;
+        assert!(!matched);
+        assert!(elapsed >= RECONCILE_TIMEOUT);
+
+        // entries that should have been removed count as drift
+        let (matched, _) = run(&[entry(m0, 1), entry(m1, 1)]).await;
+        assert!(!matched);
     }
 
-    #[test]
-    fn verify_max_remove_lst_ix_per_tx() {
-        let payer = Pubkey::new_unique();
-        let admin = Pubkey::new_unique();
-        let ixs: Vec<_> = [
-            ComputeBudgetInstruction::set_compute_unit_limit(0),
-            ComputeBudgetInstruction::set_compute_unit_price(0),
-        ]
-        .into_iter()
-        .chain((0..MAX_REMOVE_LST_IX_PER_TX).map(|_| {
-            Instruction::new_with_bytes(
-                crate::PROGRAM_ID.into(),
-                SetLstFeeIxData::new(SetLstFeeIxArgs {
-                    inp_fee_nanos: 0,
-                    out_fee_nanos: 0,
-                })
-                .as_buf(),
-                keys_signer_writable_to_metas(
-                    NewRemoveLstIxAccsBuilder::start()
-                        .with_admin(admin.as_array())
-                        .with_mint(Pubkey::new_unique().as_array())
-                        .with_refund_rent_to(payer.as_array())
-                        .with_slab(&SLAB_ID)
-                        .build()
-                        .0
-                        .iter()
-                        .copied(),
-                    REMOVE_LST_IX_IS_SIGNER.0.iter(),
-                    REMOVE_LST_IX_IS_WRITER.0.iter(),
-                ),
-            )
-        }))
-        .collect();
-
-        let tx = to_mocked_tx(ixs, &payer);
-
-        let tx_byte_len = bincode::serialize(&tx).unwrap().len();
-        //eprintln!("{tx_byte_len}");
-        assert!(tx_byte_len < 1232, "{tx_byte_len}");
+    fn mock_rpc(admin: &Pubkey, onchain: &[SlabCsvEntry]) -> MockRpc {
+        MockRpc {
+            balance: 1_000_000_000_000,
+            ..Default::default()
+        }
+        .with_slab(admin, onchain)
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn run_sends_sync() {
+        let cfg = TestCfg::new();
+        let wallet = cfg.wallet.pubkey();
+        let [m0, m1] = core::array::from_fn(|_| Pubkey::new_unique());
+        let rpc = mock_rpc(&wallet, &[entry(m0, 1)]);
+        let csv = cfg.write_csv("prices.csv", &[entry(m0, 2), entry(m1, 1)]);
+
+        // mock does not execute transactions so the slab never reconciles
+        Subcmd::run(cfg.args(&["sync-prices", "--no-reconcile", &csv]), &rpc)
+            .await
+            .unwrap();
+        let sent = rpc.sent();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].message.static_account_keys()[0], wallet);
+
+        // already in sync
+        let rpc = mock_rpc(&wallet, &[entry(m0, 2), entry(m1, 1)]);
+        Subcmd::run(cfg.args(&["sync-prices", &csv]), &rpc)
+            .await
+            .unwrap();
+        assert!(rpc.sent().is_empty());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn run_negative_fee_is_not_sent() {
+        let cfg = TestCfg::new();
+        let rpc = mock_rpc(&cfg.wallet.pubkey(), &[]);
+        let [m0, m1] = core::array::from_fn(|_| Pubkey::new_unique());
+        let csv = cfg.write_csv(
+            "prices.csv",
+            &[
+                SlabCsvEntry {
+                    mint: m0,
+                    inp: 0,
+                    out: -1,
+                },
+                entry(m1, 0),
+            ],
+        );
+
+        let res = Subcmd::run(cfg.args(&["sync-prices", &csv]), &rpc).await;
+        assert!(matches!(res, Err(CliErr::Verify(_))));
+        assert!(rpc.sent().is_empty());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn run_slab_file_requires_plan_out() {
+        let cfg = TestCfg::new();
+        let rpc = MockRpc::default();
+        let slab_file = cfg.write(
+            "slab.bin",
+            crate::rpc::mock::mock_slab_data(&cfg.wallet.pubkey(), &[]),
+        );
+        let csv = cfg.write_csv("prices.csv", &[entry(Pubkey::new_unique(), 1)]);
+
+        let res = Subcmd::run(
+            cfg.args(&["--slab-file", &slab_file, "sync-prices", &csv]),
+            &rpc,
+        )
+        .await;
+        assert!(matches!(res, Err(CliErr::Input(_))));
+
+        // planning offline does not need a wallet
+        std::fs::remove_file(cfg.path("id.json")).unwrap();
+        let plan_out = cfg.path("plan.json");
+        Subcmd::run(
+            cfg.args(&[
+                "--slab-file",
+                &slab_file,
+                "sync-prices",
+                "--plan-out",
+                &plan_out,
+                &csv,
+            ]),
+            &rpc,
+        )
+        .await
+        .unwrap();
+        assert!(Path::new(&plan_out).exists());
+        assert!(rpc.sent().is_empty());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn run_journal_requires_send_actual() {
+        let cfg = TestCfg::new();
+        let rpc = mock_rpc(&cfg.wallet.pubkey(), &[]);
+        let csv = cfg.write_csv("prices.csv", &[entry(Pubkey::new_unique(), 1)]);
+        let journal = cfg.path("journal.json");
+
+        for resume in [None, Some("--resume")] {
+            let args: Vec<_> = [
+                "--send-mode",
+                "squads",
+                "sync-prices",
+                "--journal",
+                journal.as_str(),
+            ]
+            .into_iter()
+            .chain(resume)
+            .chain([csv.as_str()])
+            .collect();
+            let res = Subcmd::run(cfg.args(&args), &rpc).await;
+            assert!(matches!(res, Err(CliErr::Input(_))));
+        }
+        assert!(rpc.sent().is_empty());
+        assert!(!Path::new(&journal).exists());
     }
 }
@@ -2,11 +2,11 @@ use std::io;
 
 use clap::Args;
 use inf1_pp_flatslab_core::accounts::Slab;
-use solana_rpc_client::nonblocking::rpc_client::RpcClient;
 
 use crate::{
+    err::CliResult,
+    rpc::Rpc,
     slabcsv::{SlabCsvEntry, write_slab_csv},
-    utils::fetch_slab_data,
 };
 
 #[derive(Args, Debug)]
@@ -14,15 +14,59 @@ use crate::{
 pub struct ViewPricesArgs;
 
 impl ViewPricesArgs {
-    pub async fn run(&self, crate::Args { config, .. }: &crate::Args) {
-        let rpc = RpcClient::new(config.json_rpc_url.to_owned());
-
-        let slab_d = fetch_slab_data(&rpc).await;
+    pub async fn run(
+        &self,
+        crate::Args { slab_src, .. }: &crate::Args,
+        rpc: &dyn Rpc,
+    ) -> CliResult<()> {
+        let slab_d = slab_src.fetch(rpc).await?;
         let slab = Slab::of_acc_data(&slab_d).unwrap();
 
         write_slab_csv(
             io::stdout(),
             slab.entries().0.iter().map(|e| SlabCsvEntry::from(*e)),
-        );
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_pubkey::Pubkey;
+
+    use crate::{
+        err::CliErr,
+        rpc::mock::{MockRpc, mock_slab_data},
+        subcmd::Subcmd,
+        testcfg::TestCfg,
+    };
+
+    #[tokio::test]
+    async fn run_reads_slab_file_or_onchain() {
+        let cfg = TestCfg::new();
+        let admin = Pubkey::new_unique();
+        let entries = [crate::slabcsv::SlabCsvEntry {
+            mint: Pubkey::new_unique(),
+            inp: 1,
+            out: 2,
+        }];
+
+        Subcmd::run(
+            cfg.args(&["view-prices"]),
+            &MockRpc::default().with_slab(&admin, &entries),
+        )
+        .await
+        .unwrap();
+
+        let slab_file = cfg.write("slab.bin", mock_slab_data(&admin, &entries));
+        Subcmd::run(
+            cfg.args(&["--slab-file", &slab_file, "view-prices"]),
+            &MockRpc::default(),
+        )
+        .await
+        .unwrap();
+
+        // not initialized
+        let res = Subcmd::run(cfg.args(&["view-prices"]), &MockRpc::default()).await;
+        assert!(matches!(res, Err(CliErr::Config(_))));
     }
 }
@@ -0,0 +1,19 @@
+//! serde for base58 types that roundtrip through their `Display` and `FromStr` impls
+//! e.g. `Hash`, `Signature`
+
+use std::{fmt::Display, str::FromStr};
+
+use serde::{Deserialize, Deserializer, Serializer, de::Error};
+
+pub fn serialize<T: Display, S: Serializer>(v: &T, s: S) -> Result<S::Ok, S::Error> {
+    s.collect_str(v)
+}
+
+pub fn deserialize<'de, T, D>(d: D) -> Result<T, D::Error>
+where
+    T: FromStr,
+    T::Err: Display,
+    D: Deserializer<'de>,
+{
+    String::deserialize(d)?.parse().map_err(Error::custom)
+}
@@ -0,0 +1,556 @@
+use std::{
+    fs::{File, rename},
+    io::{BufReader, BufWriter},
+    path::{Path, PathBuf},
+};
+
+use inf1_pp_flatslab_core::accounts::Slab;
+use serde::{Deserialize, Serialize};
+use solana_commitment_config::CommitmentConfig;
+use solana_hash::Hash;
+use solana_signature::Signature;
+
+use crate::{
+    err::{CliErr, CliResult},
+    syncplan::{SyncExec, SyncOp, b58, op_msg, print_ops},
+};
+
+/// Progress of a sync, persisted to disk as it runs so that it can be resumed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyncJournal {
+    pub batches: Vec<JournalBatch>,
+}
+
+/// The ops of a single transaction and how far it has gotten
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JournalBatch {
+    pub ops: Vec<SyncOp>,
+
+    #[serde(flatten)]
+    pub status: BatchStatus,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum BatchStatus {
+    Pending,
+
+    /// Sent but not confirmed, may or may not have landed
+    Sent {
+        #[serde(with = "b58")]
+        signature: Signature,
+
+        #[serde(with = "b58")]
+        blockhash: Hash,
+    },
+
+    Confirmed {
+        #[serde(with = "b58")]
+        signature: Signature,
+    },
+}
+
+impl SyncJournal {
+    pub fn new(batches: impl IntoIterator<Item = Vec<SyncOp>>) -> Self {
+        Self {
+            batches: batches
+                .into_iter()
+                .map(|ops| JournalBatch {
+                    ops,
+                    status: BatchStatus::Pending,
+                })
+                .collect(),
+        }
+    }
+
+    /// Ops of each batch that has not been confirmed, in order
+    pub fn remaining(&self) -> Vec<Vec<SyncOp>> {
+        self.batches
+            .iter()
+            .filter(|b| !matches!(b.status, BatchStatus::Confirmed { .. }))
+            .map(|b| b.ops.clone())
+            .collect()
+    }
+
+    /// Errs if the ops of confirmed batches are not reflected in `slab`,
+    /// i.e. something else has changed their entries since
+    pub fn check_applied(&self, slab: &Slab) -> CliResult<()> {
+        let curr_entries = slab.entries();
+        self.batches
+            .iter()
+            .filter(|b| matches!(b.status, BatchStatus::Confirmed { .. }))
+            .flat_map(|b| b.ops.iter())
+            .try_for_each(|op| {
+                let curr = curr_entries.find_by_mint(op.mint().as_array()).ok();
+                let applied = match (op, curr) {
+                    (
+                        SyncOp::SetLstFee {
+                            inp_fee_nanos,
+                            out_fee_nanos,
+                            ..
+                        },
+                        Some(curr),
+                    ) => {
+                        curr.inp_fee_nanos() == *inp_fee_nanos
+                            && curr.out_fee_nanos() == *out_fee_nanos
+                    }
+                    (SyncOp::SetLstFee { .. }, None) => false,
+                    (SyncOp::RemoveLst { .. }, curr) => curr.is_none(),
+                };
+                if applied {
+                    Ok(())
+                } else {
+                    Err(CliErr::Verify(format!(
+                        "Slab has changed since the journal was written, confirmed op {} is no longer reflected onchain",
+                        op_msg(op)
+                    )))
+                }
+            })
+    }
+}
+
+pub fn read_sync_journal_file(p: impl AsRef<Path>) -> CliResult<SyncJournal> {
+    let f = BufReader::new(
+        File::open(p)
+            .map_err(|e| CliErr::Input(format!("Failed to read sync journal file: {e}")))?,
+    );
+    serde_json::from_reader(f)
+        .map_err(|e| CliErr::Input(format!("Failed to deserialize sync journal: {e}")))
+}
+
+/// Writes to a temp file then renames it over `p`
+/// so that the journal is never left half-written
+pub fn write_sync_journal_file(p: impl AsRef<Path>, journal: &SyncJournal) -> CliResult<()> {
+    let p = p.as_ref();
+    let mut tmp = p.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+    let write_err = |e: String| CliErr::Input(format!("Failed to write sync journal file: {e}"));
+    let mut f = BufWriter::new(
+        File::create(&tmp)
+            .map_err(|e| CliErr::Input(format!("Failed to create sync journal file: {e}")))?,
+    );
+    serde_json::to_writer_pretty(&mut f, journal).map_err(|e| write_err(e.to_string()))?;
+    f.into_inner()
+        .map_err(|e| write_err(e.to_string()))?
+        .sync_all()
+        .map_err(|e| write_err(e.to_string()))?;
+    rename(&tmp, p).map_err(|e| write_err(e.to_string()))
+}
+
+impl SyncExec<'_> {
+    /// Checks the batches that were sent but not confirmed by a previous run,
+    /// marking each confirmed if it landed or pending if it never will
+    /// so that they are never applied twice.
+    pub async fn resolve_sent(
+        &self,
+        journal: &mut SyncJournal,
+        journal_path: &Path,
+    ) -> CliResult<()> {
+        for i in 0..journal.batches.len() {
+            if let BatchStatus::Sent {
+                signature,
+                blockhash,
+            } = journal.batches[i].status
+            {
+                journal.batches[i].status = self.check_sent(signature, blockhash).await?;
+                write_sync_journal_file(journal_path, journal)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Sends every batch of `journal` that has not been confirmed yet,
+    /// recording the progress of each batch to `journal_path` as it goes.
+    ///
+    /// Runs [`Self::resolve_sent`] first.
+    pub async fn exec_journaled(
+        &self,
+        mut journal: SyncJournal,
+        journal_path: &Path,
+    ) -> CliResult<()> {
+        self.resolve_sent(&mut journal, journal_path).await?;
+
+        let n_batches = journal.batches.len();
+        let remaining = journal.remaining();
+        if remaining.is_empty() {
+            eprintln!("Every batch in the journal is already confirmed");
+            return Ok(());
+        }
+        print_ops(&remaining.concat());
+        self.check_costs(&remaining).await?;
+        self.confirm(remaining.len())?;
+
+        for i in 0..n_batches {
+            if let BatchStatus::Confirmed { signature } = journal.batches[i].status {
+                eprintln!("Batch {}/{n_batches} already confirmed: {signature}", i + 1);
+                continue;
+            }
+
+            // every re-signed transaction is recorded before it is sent
+            let res = self
+                .ctx
+                .send_loop
+                .send(self.ctx.rpc, async || {
+                    let tx = self.to_tx(&journal.batches[i].ops).await?;
+                    journal.batches[i].status = BatchStatus::Sent {
+                        signature: tx.signatures[0],
+                        blockhash: *tx.message.recent_blockhash(),
+                    };
+                    write_sync_journal_file(journal_path, &journal)?;
+                    Ok(tx)
+                })
+                .await;
+            match res {
+                Ok(signature) => {
+                    journal.batches[i].status = BatchStatus::Confirmed { signature };
+                    write_sync_journal_file(journal_path, &journal)?;
+                    eprintln!("Batch {}/{n_batches} confirmed: {signature}", i + 1);
+                }
+                Err(e) => {
+                    eprintln!(
+                        "Progress saved to {}, rerun with --resume to continue",
+                        journal_path.display()
+                    );
+                    return Err(e);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn check_sent(&self, signature: Signature, blockhash: Hash) -> CliResult<BatchStatus> {
+        let rpc = self.ctx.rpc;
+        let status = rpc
+            .get_signature_statuses_with_history(&[signature])
+            .await?
+            .value
+            .into_iter()
+            .next()
+            .flatten();
+        Ok(match status {
+            // may still be rolled back
+            Some(s) if !s.satisfies_commitment(CommitmentConfig::confirmed()) => {
+                return Err(CliErr::Onchain(format!(
+                    "Transaction {signature} has landed but is not confirmed yet, rerun with --resume once it is"
+                )));
+            }
+            Some(s) if s.err.is_none() => BatchStatus::Confirmed { signature },
+            // failed onchain, none of its ops were applied
+            Some(_) => BatchStatus::Pending,
+            None => {
+                let may_land = match self.ctx.nonce {
+                    // nonce is advanced once a transaction using it lands
+                    Some(nonce) => nonce.fetch_blockhash(rpc).await? == blockhash,
+                    None => {
+                        rpc.is_blockhash_valid(&blockhash, CommitmentConfig::processed())
+                            .await?
+                    }
+                };
+                if may_land {
+                    return Err(CliErr::Onchain(format!(
+                        "Transaction {signature} may still land, rerun with --resume after its blockhash expires or nonce is advanced"
+                    )));
+                }
+                BatchStatus::Pending
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_keypair::Keypair;
+    use solana_pubkey::Pubkey;
+    use solana_signer::Signer;
+    use solana_transaction_error::TransactionError;
+    use solana_transaction_status_client_types::TransactionConfirmationStatus;
+
+    use crate::{
+        rpc::mock::{MockRpc, mock_slab_data, mock_status},
+        slabcsv::SlabCsvEntry,
+        sscu::TxSendMode,
+        testcfg::{TestCfg, test_ctx},
+    };
+
+    use super::*;
+
+    const STALE_BLOCKHASH: Hash = Hash::new_from_array([1; 32]);
+
+    fn set_fee(mint: Pubkey) -> Vec<SyncOp> {
+        vec![SyncOp::SetLstFee {
+            mint,
+            inp_fee_nanos: 1,
+            out_fee_nanos: 2,
+        }]
+    }
+
+    fn exec<'a>(rpc: &'a MockRpc, slab_d: &'a [u8], payer: &'a Keypair) -> SyncExec<'a> {
+        SyncExec {
+            slab_d,
+            payer,
+            admin: payer,
+            ctx: test_ctx(rpc, TxSendMode::SendActual),
+            yes: true,
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn exec_journaled_resumes() {
+        let payer = Keypair::new();
+        let [m0, m1, m2, m3] = core::array::from_fn(|_| Pubkey::new_unique());
+        let [sig0, sig1, sig2] = [0, 1, 2].map(|i| Signature::from([i; 64]));
+        let slab_d = mock_slab_data(
+            &payer.pubkey(),
+            &[SlabCsvEntry {
+                mint: m0,
+                inp: 1,
+                out: 2,
+            }],
+        );
+        let rpc = MockRpc {
+            balance: u64::MAX / 2,
+            ..Default::default()
+        }
+        .with_status(
+            sig1,
+            mock_status(TransactionConfirmationStatus::Confirmed, None),
+        );
+        let cfg = TestCfg::new();
+        let path = cfg.path("journal.json");
+
+        let journal = SyncJournal {
+            batches: vec![
+                JournalBatch {
+                    ops: set_fee(m0),
+                    status: BatchStatus::Confirmed { signature: sig0 },
+                },
+                // landed
+                JournalBatch {
+                    ops: set_fee(m1),
+                    status: BatchStatus::Sent {
+                        signature: sig1,
+                        blockhash: STALE_BLOCKHASH,
+                    },
+                },
+                // expired
+                JournalBatch {
+                    ops: set_fee(m2),
+                    status: BatchStatus::Sent {
+                        signature: sig2,
+                        blockhash: STALE_BLOCKHASH,
+                    },
+                },
+                JournalBatch {
+                    ops: set_fee(m3),
+                    status: BatchStatus::Pending,
+                },
+            ],
+        };
+        exec(&rpc, &slab_d, &payer)
+            .exec_journaled(journal, Path::new(&path))
+            .await
+            .unwrap();
+
+        let sent = rpc.sent();
+        assert_eq!(sent.len(), 2);
+        let res = read_sync_journal_file(&path).unwrap();
+        assert_eq!(
+            res.batches.iter().map(|b| b.status).collect::<Vec<_>>(),
+            vec![
+                BatchStatus::Confirmed { signature: sig0 },
+                BatchStatus::Confirmed { signature: sig1 },
+                BatchStatus::Confirmed {
+                    signature: sent[0].signatures[0]
+                },
+                BatchStatus::Confirmed {
+                    signature: sent[1].signatures[0]
+                },
+            ]
+        );
+        assert!(res.remaining().is_empty());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn exec_journaled_all_confirmed_does_not_confirm() {
+        let payer = Keypair::new();
+        let slab_d = mock_slab_data(&payer.pubkey(), &[]);
+        let rpc = MockRpc::default();
+        let journal = SyncJournal {
+            batches: vec![JournalBatch {
+                ops: set_fee(Pubkey::new_unique()),
+                status: BatchStatus::Confirmed {
+                    signature: Signature::from([0; 64]),
+                },
+            }],
+        };
+        // without --yes, confirming errs when stdin is not a terminal
+        SyncExec {
+            yes: false,
+            ..exec(&rpc, &slab_d, &payer)
+        }
+        .exec_journaled(journal, Path::new(&TestCfg::new().path("journal.json")))
+        .await
+        .unwrap();
+        assert!(rpc.sent().is_empty());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn exec_journaled_costs_exclude_resolved() {
+        let payer = Keypair::new();
+        let [m0, m1] = core::array::from_fn(|_| Pubkey::new_unique());
+        let sig0 = Signature::from([0; 64]);
+        let slab_d = mock_slab_data(&payer.pubkey(), &[]);
+        let cfg = TestCfg::new();
+        let path = cfg.path("journal.json");
+
+        // exactly enough for the pending batch only
+        let balance = exec(&MockRpc::default(), &slab_d, &payer)
+            .costs(&[set_fee(m1)])
+            .await
+            .unwrap()
+            .required();
+        let rpc = MockRpc {
+            balance,
+            ..Default::default()
+        }
+        .with_status(
+            sig0,
+            mock_status(TransactionConfirmationStatus::Finalized, None),
+        );
+
+        let journal = SyncJournal {
+            batches: vec![
+                JournalBatch {
+                    ops: set_fee(m0),
+                    status: BatchStatus::Sent {
+                        signature: sig0,
+                        blockhash: STALE_BLOCKHASH,
+                    },
+                },
+                JournalBatch {
+                    ops: set_fee(m1),
+                    status: BatchStatus::Pending,
+                },
+            ],
+        };
+        let res = exec(&rpc, &slab_d, &payer)
+            .exec_journaled(journal, Path::new(&path))
+            .await;
+        res.unwrap();
+        assert_eq!(rpc.sent().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn check_sent_requires_confirmed() {
+        let payer = Keypair::new();
+        let slab_d = mock_slab_data(&payer.pubkey(), &[]);
+        let [processed, confirmed, failed, unknown] =
+            [0, 1, 2, 3].map(|i| Signature::from([i; 64]));
+        let rpc = MockRpc::default()
+            .with_status(
+                processed,
+                mock_status(TransactionConfirmationStatus::Processed, None),
+            )
+            .with_status(
+                confirmed,
+                mock_status(TransactionConfirmationStatus::Confirmed, None),
+            )
+            .with_status(
+                failed,
+                mock_status(
+                    TransactionConfirmationStatus::Finalized,
+                    Some(TransactionError::AccountInUse),
+                ),
+            );
+        let exec = exec(&rpc, &slab_d, &payer);
+
+        // may still be rolled back
+        assert!(matches!(
+            exec.check_sent(processed, STALE_BLOCKHASH).await,
+            Err(CliErr::Onchain(_))
+        ));
+        assert_eq!(
+            exec.check_sent(confirmed, STALE_BLOCKHASH).await.unwrap(),
+            BatchStatus::Confirmed {
+                signature: confirmed
+            }
+        );
+        assert_eq!(
+            exec.check_sent(failed, STALE_BLOCKHASH).await.unwrap(),
+            BatchStatus::Pending
+        );
+        // blockhash still valid, may still land
+        assert!(matches!(
+            exec.check_sent(unknown, rpc.blockhash).await,
+            Err(CliErr::Onchain(_))
+        ));
+        assert_eq!(
+            exec.check_sent(unknown, STALE_BLOCKHASH).await.unwrap(),
+            BatchStatus::Pending
+        );
+    }
+
+    #[test]
+    fn write_journal_tmp_path() {
+        let cfg = TestCfg::new();
+        // with_extension("tmp") would map this path to itself
+        let path = cfg.path("journal.tmp");
+        let journal = SyncJournal::new([set_fee(Pubkey::new_unique())]);
+        write_sync_journal_file(&path, &journal).unwrap();
+        assert_eq!(read_sync_journal_file(&path).unwrap(), journal);
+        assert!(!Path::new(&cfg.path("journal.tmp.tmp")).exists());
+    }
+
+    #[test]
+    fn check_applied() {
+        let admin = Pubkey::new_unique();
+        let [m0, m1] = core::array::from_fn(|_| Pubkey::new_unique());
+        let mut journal = SyncJournal::new([set_fee(m0), vec![SyncOp::RemoveLst { mint: m1 }]]);
+        journal.batches.iter_mut().for_each(|b| {
+            b.status = BatchStatus::Confirmed {
+                signature: Signature::default(),
+            }
+        });
+        let applied = mock_slab_data(
+            &admin,
+            &[SlabCsvEntry {
+                mint: m0,
+                inp: 1,
+                out: 2,
+            }],
+        );
+        let overwritten = mock_slab_data(
+            &admin,
+            &[SlabCsvEntry {
+                mint: m0,
+                inp: 3,
+                out: 2,
+            }],
+        );
+        let readded = mock_slab_data(
+            &admin,
+            &[
+                SlabCsvEntry {
+                    mint: m0,
+                    inp: 1,
+                    out: 2,
+                },
+                SlabCsvEntry {
+                    mint: m1,
+                    inp: 0,
+                    out: 0,
+                },
+            ],
+        );
+
+        let check = |d: &[u8]| journal.check_applied(&Slab::of_acc_data(d).unwrap());
+        check(&applied).unwrap();
+        assert!(matches!(check(&overwritten), Err(CliErr::Verify(_))));
+        assert!(matches!(check(&readded), Err(CliErr::Verify(_))));
+
+        // pending batches are not checked
+        journal.batches[1].status = BatchStatus::Pending;
+        check(&readded).unwrap();
+    }
+}
@@ -0,0 +1,561 @@
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::{BufReader, BufWriter},
+    path::Path,
+};
+
+use inf1_pp_flatslab_core::{
+    accounts::Slab,
+    instructions::admin::{
+        remove_lst::{
+            NewRemoveLstIxAccsBuilder, REMOVE_LST_IX_IS_SIGNER, REMOVE_LST_IX_IS_WRITER,
+            RemoveLstIxData,
+        },
+        set_lst_fee::{
+            NewSetLstFeeIxAccsBuilder, SET_LST_FEE_IX_IS_SIGNER, SET_LST_FEE_IX_IS_WRITER,
+            SetLstFeeIxArgs, SetLstFeeIxData,
+        },
+    },
+    keys::SLAB_ID,
+};
+use serde::{Deserialize, Serialize};
+use solana_hash::Hash;
+use solana_instruction::Instruction;
+use solana_pubkey::Pubkey;
+use solana_signer::Signer;
+use solana_transaction::versioned::VersionedTransaction;
+
+use crate::{
+    confirm::SendSummary,
+    costs::{LamportCosts, slab_rent_delta, tx_fees},
+    err::{CliErr, CliResult},
+    slabcsv::{SlabCsvEntry, b58pk},
+    slabdiff::{SlabDiff, SlabEntrySet},
+    sscu::TxSendMode,
+    utils::{TxCtx, keys_signer_writable_to_metas, pack_ixs},
+};
+
+pub mod b58;
+mod journal;
+
+pub use journal::*;
+
+/// A single slab admin operation of a sync
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum SyncOp {
+    SetLstFee {
+        #[serde(with = "b58pk")]
+        mint: Pubkey,
+        inp_fee_nanos: i32,
+        out_fee_nanos: i32,
+    },
+    RemoveLst {
+        #[serde(with = "b58pk")]
+        mint: Pubkey,
+    },
+}
+
+impl SyncOp {
+    pub const fn mint(&self) -> &Pubkey {
+        match self {
+            Self::SetLstFee { mint, .. } | Self::RemoveLst { mint } => mint,
+        }
+    }
+
+    /// `payer` funds the slab realloc for `SetLstFee`
+    /// and is refunded the rent for `RemoveLst`
+    pub fn to_ix(&self, admin: &[u8; 32], payer: &[u8; 32]) -> Instruction {
+        match self {
+            Self::SetLstFee {
+                mint,
+                inp_fee_nanos,
+                out_fee_nanos,
+            } => Instruction::new_with_bytes(
+                crate::PROGRAM_ID.into(),
+                SetLstFeeIxData::new(SetLstFeeIxArgs {
+                    inp_fee_nanos: *inp_fee_nanos,
+                    out_fee_nanos: *out_fee_nanos,
+                })
+                .as_buf(),
+                keys_signer_writable_to_metas(
+                    NewSetLstFeeIxAccsBuilder::start()
+                        .with_admin(admin)
+                        .with_mint(mint.as_array())
+                        .with_payer(payer)
+                        .with_slab(&SLAB_ID)
+                        .with_system_program(&[0u8; 32])
+                        .build()
+                        .0
+                        .iter()
+                        .copied(),
+                    SET_LST_FEE_IX_IS_SIGNER.0.iter(),
+                    SET_LST_FEE_IX_IS_WRITER.0.iter(),
+                ),
+            ),
+            Self::RemoveLst { mint } => Instruction::new_with_bytes(
+                crate::PROGRAM_ID.into(),
+                RemoveLstIxData::new().as_buf(),
+                keys_signer_writable_to_metas(
+                    NewRemoveLstIxAccsBuilder::start()
+                        .with_admin(admin)
+                        .with_mint(mint.as_array())
+                        .with_refund_rent_to(payer)
+                        .with_slab(&SLAB_ID)
+                        .build()
+                        .0
+                        .iter()
+                        .copied(),
+                    REMOVE_LST_IX_IS_SIGNER.0.iter(),
+                    REMOVE_LST_IX_IS_WRITER.0.iter(),
+                ),
+            ),
+        }
+    }
+}
+
+/// The set of operations that bring the slab in line with a csv,
+/// along with the hash of the slab account data it was computed against
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SyncPlan {
+    #[serde(with = "b58")]
+    pub slab_data_hash: Hash,
+
+    /// All `SetLstFee`s come before all `RemoveLst`s
+    pub ops: Vec<SyncOp>,
+}
+
+impl SyncPlan {
+    pub fn new(slab_d: &[u8], SlabDiff { sets, removes }: &SlabDiff) -> Self {
+        let ops = sets
+            .iter()
+            .map(|SlabEntrySet { new, .. }| SyncOp::SetLstFee {
+                mint: new.mint,
+                inp_fee_nanos: new.inp,
+                out_fee_nanos: new.out,
+            })
+            .chain(removes.iter().map(|e| SyncOp::RemoveLst { mint: e.mint }))
+            .collect();
+        Self {
+            slab_data_hash: slab_data_hash(slab_d),
+            ops,
+        }
+    }
+}
+
+/// Returns the slab entries that result from running `ops` on `curr`, sorted by mint
+pub fn project_entries(
+    curr: impl IntoIterator<Item = SlabCsvEntry>,
+    ops: &[SyncOp],
+) -> Vec<SlabCsvEntry> {
+    let mut res: BTreeMap<Pubkey, SlabCsvEntry> = curr.into_iter().map(|e| (e.mint, e)).collect();
+    ops.iter().for_each(|op| match op {
+        SyncOp::SetLstFee {
+            mint,
+            inp_fee_nanos,
+            out_fee_nanos,
+        } => {
+            res.insert(
+                *mint,
+                SlabCsvEntry {
+                    mint: *mint,
+                    inp: *inp_fee_nanos,
+                    out: *out_fee_nanos,
+                },
+            );
+        }
+        SyncOp::RemoveLst { mint } => {
+            res.remove(mint);
+        }
+    });
+    res.into_values().collect()
+}
+
+/// The entry changes made by the `SetLstFee`s of `ops` to `slab`, in order
+pub fn op_entry_sets(slab: &Slab, ops: &[SyncOp]) -> Vec<SlabEntrySet> {
+    let curr_entries = slab.entries();
+    ops.iter()
+        .filter_map(|op| match op {
+            SyncOp::SetLstFee {
+                mint,
+                inp_fee_nanos,
+                out_fee_nanos,
+            } => Some(SlabEntrySet {
+                old: curr_entries
+                    .find_by_mint(mint.as_array())
+                    .ok()
+                    .map(|curr| SlabCsvEntry {
+                        mint: *mint,
+                        inp: curr.inp_fee_nanos(),
+                        out: curr.out_fee_nanos(),
+                    }),
+                new: SlabCsvEntry {
+                    mint: *mint,
+                    inp: *inp_fee_nanos,
+                    out: *out_fee_nanos,
+                },
+            }),
+            SyncOp::RemoveLst { .. } => None,
+        })
+        .collect()
+}
+
+pub fn slab_data_hash(slab_d: &[u8]) -> Hash {
+    solana_sha256_hasher::hash(slab_d)
+}
+
+pub fn read_sync_plan_file(p: impl AsRef<Path>) -> CliResult<SyncPlan> {
+    let f = BufReader::new(
+        File::open(p).map_err(|e| CliErr::Input(format!("Failed to read sync plan file: {e}")))?,
+    );
+    serde_json::from_reader(f)
+        .map_err(|e| CliErr::Input(format!("Failed to deserialize sync plan: {e}")))
+}
+
+pub fn write_sync_plan_file(p: impl AsRef<Path>, plan: &SyncPlan) -> CliResult<()> {
+    let f = BufWriter::new(
+        File::create(p)
+            .map_err(|e| CliErr::Input(format!("Failed to create sync plan file: {e}")))?,
+    );
+    serde_json::to_writer_pretty(f, plan)
+        .map_err(|e| CliErr::Input(format!("Failed to write sync plan: {e}")))
+}
+
+/// What's needed to turn [`SyncOp`]s into transactions
+#[derive(Clone, Copy)]
+pub struct SyncExec<'a> {
+    /// The slab's current account data
+    pub slab_d: &'a [u8],
+
+    /// Should be the proposer's executor if `ctx.proposer` is set
+    pub payer: &'a dyn Signer,
+
+    /// Signer for the slab's current admin,
+    /// may be a `NullSigner` for dumped transactions
+    pub admin: &'a dyn Signer,
+
+    pub ctx: TxCtx<'a>,
+
+    /// Skip the confirmation prompt
+    pub yes: bool,
+}
+
+impl SyncExec<'_> {
+    /// Packs `ops` into as few transaction batches as possible.
+    /// Each op must be for a different mint since ops may share a transaction.
+    ///
+    /// Errs if `ops` do not fit into a single transaction and are to be dumped with a nonce,
+    /// since only the first transaction to advance it could ever land.
+    pub fn pack(&self, ops: &[SyncOp]) -> CliResult<Vec<Vec<SyncOp>>> {
+        let mut mints: Vec<_> = ops.iter().map(|op| op.mint()).collect();
+        mints.sort_unstable();
+        if let Some(s) = mints.windows(2).find(|s| s[0] == s[1]) {
+            return Err(CliErr::Input(format!("Multiple ops for mint {}", s[0])));
+        }
+
+        let payer_pk = self.payer.pubkey();
+        let mut ops_iter = ops.iter().copied();
+        let batches: Vec<_> = pack_ixs(self.to_ixs(ops), |batch| {
+            self.ctx.est_tx_size(&payer_pk, batch)
+        })?
+        .into_iter()
+        .map(|batch| ops_iter.by_ref().take(batch.len()).collect())
+        .collect();
+
+        let is_dump = matches!(
+            self.ctx.send_mode,
+            TxSendMode::Dump64 | TxSendMode::Dump58 | TxSendMode::DumpMsg | TxSendMode::DumpIxs
+        );
+        if is_dump && self.ctx.nonce.is_some() && batches.len() > 1 {
+            return Err(CliErr::Input(format!(
+                "{} ops require {} transactions but dumped transactions cannot share a nonce, sync fewer mints at a time or drop --nonce",
+                ops.len(),
+                batches.len()
+            )));
+        }
+        Ok(batches)
+    }
+
+    pub async fn exec(&self, ops: &[SyncOp]) -> CliResult<()> {
+        if ops.is_empty() {
+            eprintln!("Slab entries already in sync");
+            return Ok(());
+        }
+        print_ops(ops);
+        let batches = self.pack(ops)?;
+        self.check_costs(&batches).await?;
+        self.confirm(batches.len())?;
+        for batch in batches {
+            self.ctx
+                .send(self.to_ixs(&batch), vec![self.payer, self.admin])
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// What it costs the payer to send `batches`
+    pub async fn costs(&self, batches: &[Vec<SyncOp>]) -> CliResult<LamportCosts> {
+        let TxCtx {
+            rpc,
+            send_mode,
+            prio_fee,
+            ..
+        } = self.ctx;
+        let slab = Slab::of_acc_data(self.slab_d).unwrap();
+        let curr_entries = slab.entries();
+        let (n_added, n_removed) =
+            batches
+                .iter()
+                .flatten()
+                .fold((0, 0), |(n_added, n_removed), op| match op {
+                    SyncOp::SetLstFee { mint, .. } => (
+                        n_added + usize::from(curr_entries.find_by_mint(mint.as_array()).is_err()),
+                        n_removed,
+                    ),
+                    SyncOp::RemoveLst { .. } => (n_added, n_removed + 1),
+                });
+        let (rent_paid, rent_refunded) =
+            slab_rent_delta(rpc, self.slab_d.len(), n_added, n_removed).await?;
+
+        let n_sigs = self.signer_pks().len();
+
+        Ok(LamportCosts {
+            rent_paid,
+            rent_refunded,
+            tx_fees: tx_fees(
+                batches.iter().map(|_| n_sigs),
+                send_mode,
+                prio_fee.max_lamports(),
+            ),
+        })
+    }
+
+    /// Prints [`Self::costs`], erring if the payer cannot afford them
+    pub async fn check_costs(&self, batches: &[Vec<SyncOp>]) -> CliResult<()> {
+        let TxCtx { rpc, send_mode, .. } = self.ctx;
+        let payer_pk = self.payer.pubkey();
+        self.costs(batches)
+            .await?
+            .check_payers(rpc, &self.ctx.fee_payer(&payer_pk), &payer_pk, send_mode)
+            .await
+    }
+
+    /// Asks the user to confirm sending `n_txs` transactions
+    pub fn confirm(&self, n_txs: usize) -> CliResult<()> {
+        SendSummary {
+            rpc: self.ctx.rpc,
+            signers: &self.signer_pks(),
+            n_txs,
+        }
+        .confirm(self.ctx.send_mode, self.yes)
+    }
+
+    fn signer_pks(&self) -> Vec<Pubkey> {
+        self.ctx
+            .signer_pks(&[self.payer.pubkey(), self.admin.pubkey()])
+    }
+
+    fn to_ixs(&self, ops: &[SyncOp]) -> Vec<Instruction> {
+        let slab = Slab::of_acc_data(self.slab_d).unwrap();
+        let payer_pk = self.payer.pubkey();
+        ops.iter()
+            .map(|op| op.to_ix(slab.admin(), payer_pk.as_array()))
+            .collect()
+    }
+
+    async fn to_tx(&self, batch: &[SyncOp]) -> CliResult<VersionedTransaction> {
+        self.ctx
+            .to_tx(self.to_ixs(batch), vec![self.payer, self.admin])
+            .await
+    }
+}
+
+pub fn print_ops(ops: &[SyncOp]) {
+    for (title, is_set) in [("Setting:", true), ("Removing:", false)] {
+        let mut filtered = ops
+            .iter()
+            .filter(|op| matches!(op, SyncOp::SetLstFee { .. }) == is_set)
+            .peekable();
+        if filtered.peek().is_some() {
+            eprintln!("{title}");
+            filtered.for_each(|op| eprintln!("{}", op_msg(op)));
+        }
+    }
+}
+
+fn op_msg(op: &SyncOp) -> String {
+    match op {
+        SyncOp::SetLstFee {
+            mint,
+            inp_fee_nanos,
+            out_fee_nanos,
+        } => format!("{mint} inp={inp_fee_nanos} out={out_fee_nanos}"),
+        SyncOp::RemoveLst { mint } => mint.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_keypair::Keypair;
+
+    use crate::{
+        nonce::DurableNonce,
+        rpc::mock::{MockRpc, mock_slab_data},
+        testcfg::test_ctx,
+    };
+
+    use super::*;
+
+    #[test]
+    fn pack_dump_nonce_single_tx() {
+        let payer = Keypair::new();
+        let slab_d = mock_slab_data(&payer.pubkey(), &[]);
+        let rpc = MockRpc::default();
+        let nonce = DurableNonce {
+            account: Pubkey::new_unique(),
+            authority: Box::new(Keypair::new()),
+        };
+        let exec = |send_mode, nonce| SyncExec {
+            slab_d: &slab_d,
+            payer: &payer,
+            admin: &payer,
+            ctx: TxCtx {
+                nonce,
+                ..test_ctx(&rpc, send_mode)
+            },
+            yes: true,
+        };
+        let ops = |n| -> Vec<_> {
+            (0..n)
+                .map(|_| SyncOp::RemoveLst {
+                    mint: Pubkey::new_unique(),
+                })
+                .collect()
+        };
+
+        let many = ops(100);
+        assert!(
+            exec(TxSendMode::SendActual, Some(&nonce))
+                .pack(&many)
+                .unwrap()
+                .len()
+                > 1
+        );
+        assert!(exec(TxSendMode::Dump64, None).pack(&many).unwrap().len() > 1);
+        assert!(matches!(
+            exec(TxSendMode::Dump64, Some(&nonce)).pack(&many),
+            Err(CliErr::Input(_))
+        ));
+        assert_eq!(
+            exec(TxSendMode::Dump64, Some(&nonce))
+                .pack(&ops(1))
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn exec_no_ops_does_not_confirm() {
+        let payer = Keypair::new();
+        let slab_d = mock_slab_data(&payer.pubkey(), &[]);
+        let rpc = MockRpc::default();
+        // without --yes, confirming errs when stdin is not a terminal
+        SyncExec {
+            slab_d: &slab_d,
+            payer: &payer,
+            admin: &payer,
+            ctx: test_ctx(&rpc, TxSendMode::SendActual),
+            yes: false,
+        }
+        .exec(&[])
+        .await
+        .unwrap();
+        assert!(rpc.sent().is_empty());
+    }
+
+    #[test]
+    fn project_entries_applies_ops() {
+        let [a, b, c] = core::array::from_fn(|_| Pubkey::new_unique());
+        let entry = |mint, inp, out| SlabCsvEntry { mint, inp, out };
+        let mut expected = vec![entry(a, 1, 1), entry(c, 3, 3)];
+        expected.sort_unstable_by_key(|e| e.mint);
+
+        assert_eq!(
+            project_entries(
+                [entry(a, 0, 0), entry(b, 0, 0)],
+                &[
+                    SyncOp::SetLstFee {
+                        mint: a,
+                        inp_fee_nanos: 1,
+                        out_fee_nanos: 1,
+                    },
+                    SyncOp::SetLstFee {
+                        mint: c,
+                        inp_fee_nanos: 3,
+                        out_fee_nanos: 3,
+                    },
+                    SyncOp::RemoveLst { mint: b },
+                ],
+            ),
+            expected
+        );
+    }
+
+    #[test]
+    fn op_entry_sets_old_from_slab() {
+        let [a, b, c] = core::array::from_fn(|_| Pubkey::new_unique());
+        let entry = |mint, inp, out| SlabCsvEntry { mint, inp, out };
+        let slab_d = mock_slab_data(&Pubkey::new_unique(), &[entry(a, 1, 1), entry(b, 2, 2)]);
+        let slab = Slab::of_acc_data(&slab_d).unwrap();
+
+        assert_eq!(
+            op_entry_sets(
+                &slab,
+                &[
+                    SyncOp::SetLstFee {
+                        mint: a,
+                        inp_fee_nanos: 3,
+                        out_fee_nanos: 4,
+                    },
+                    SyncOp::SetLstFee {
+                        mint: c,
+                        inp_fee_nanos: 5,
+                        out_fee_nanos: 6,
+                    },
+                    SyncOp::RemoveLst { mint: b },
+                ],
+            ),
+            [
+                SlabEntrySet {
+                    old: Some(entry(a, 1, 1)),
+                    new: entry(a, 3, 4),
+                },
+                SlabEntrySet {
+                    old: None,
+                    new: entry(c, 5, 6),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn sync_plan_json_roundtrip() {
+        let plan = SyncPlan {
+            slab_data_hash: slab_data_hash(&[1, 2, 3]),
+            ops: vec![
+                SyncOp::SetLstFee {
+                    mint: Pubkey::new_unique(),
+                    inp_fee_nanos: -1_000_000,
+                    out_fee_nanos: 2_000_000,
+                },
+                SyncOp::RemoveLst {
+                    mint: Pubkey::new_unique(),
+                },
+            ],
+        };
+        let s = serde_json::to_string(&plan).unwrap();
+        assert_eq!(serde_json::from_str::<SyncPlan>(&s).unwrap(), plan);
+    }
+}
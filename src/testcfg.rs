@@ -0,0 +1,102 @@
+//! Temp files for parsing [`crate::Args`] in tests the same way the binary does,
+//! and a [`TxCtx`] for tests that build one directly
+
+use std::{
+    fs::{File, create_dir, remove_dir_all, write},
+    path::PathBuf,
+    time::Duration,
+};
+
+use clap::Parser;
+use solana_cli_config::Config;
+use solana_keypair::Keypair;
+use solana_pubkey::Pubkey;
+
+use crate::{
+    priofee::PrioFee,
+    rpc::Rpc,
+    sendloop::SendLoop,
+    slabcsv::{SlabCsvEntry, write_slab_csv},
+    sscu::TxSendMode,
+    utils::TxCtx,
+};
+
+/// No priority fee, proposer, nonce or lookup table, and no retries
+pub fn test_ctx(rpc: &dyn Rpc, send_mode: TxSendMode) -> TxCtx<'_> {
+    TxCtx {
+        rpc,
+        send_mode,
+        prio_fee: PrioFee::Total(0),
+        proposer: None,
+        nonce: None,
+        lut: None,
+        send_loop: SendLoop {
+            retries: 0,
+            timeout: Duration::from_secs(60),
+        },
+    }
+}
+
+/// Temp dir with a solana config whose wallet is [`Self::wallet`],
+/// removed on drop
+pub struct TestCfg {
+    pub dir: PathBuf,
+    pub wallet: Keypair,
+}
+
+impl TestCfg {
+    pub fn new() -> Self {
+        let dir = std::env::temp_dir().join(format!("flatslab-test-{}", Pubkey::new_unique()));
+        create_dir(&dir).unwrap();
+        let wallet = Keypair::new();
+        let res = Self { dir, wallet };
+
+        let keypair_path = res.write(
+            "id.json",
+            serde_json::to_vec(&res.wallet.to_bytes().to_vec()).unwrap(),
+        );
+        Config {
+            keypair_path,
+            ..Default::default()
+        }
+        .save(&res.path("config.yml"))
+        .unwrap();
+        res
+    }
+
+    /// Path of `name` in [`Self::dir`]
+    pub fn path(&self, name: &str) -> String {
+        self.dir.join(name).to_str().unwrap().to_owned()
+    }
+
+    /// Writes `contents` to `name` in [`Self::dir`], returning its path
+    pub fn write(&self, name: &str, contents: impl AsRef<[u8]>) -> String {
+        let p = self.path(name);
+        write(&p, contents).unwrap();
+        p
+    }
+
+    /// Writes `entries` as a slab csv to `name` in [`Self::dir`], returning its path
+    pub fn write_csv(&self, name: &str, entries: &[SlabCsvEntry]) -> String {
+        let p = self.path(name);
+        write_slab_csv(File::create(&p).unwrap(), entries).unwrap();
+        p
+    }
+
+    /// Parses `flatslab --config <config> --yes <args>`
+    pub fn args(&self, args: &[&str]) -> crate::Args {
+        let config = self.path("config.yml");
+        crate::Args::try_parse_from(
+            ["flatslab", "--config", config.as_str(), "--yes"]
+                .into_iter()
+                .chain(args.iter().copied()),
+        )
+        .unwrap()
+    }
+}
+
+impl Drop for TestCfg {
+    fn drop(&mut self) {
+        let _ = remove_dir_all(&self.dir);
+    }
+}
@@ -0,0 +1,228 @@
+use std::{fs::read_to_string, path::Path};
+
+use data_encoding::BASE64;
+use serde::Serialize;
+use solana_instruction::{AccountMeta, Instruction};
+use solana_message::VersionedMessage;
+use solana_pubkey::Pubkey;
+use solana_signature::Signature;
+use solana_signer::Signer;
+use solana_transaction::versioned::VersionedTransaction;
+
+use crate::slabcsv::b58pk;
+
+/// Text encodings of serialized transactions that `TxSendMode::Dump64` and `TxSendMode::Dump58` output
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TxEncoding {
+    Base64,
+    Base58,
+}
+
+impl TxEncoding {
+    pub fn encode(&self, tx: &VersionedTransaction) -> String {
+        let ser = bincode::serialize(tx).unwrap();
+        match self {
+            Self::Base64 => BASE64.encode(&ser),
+            Self::Base58 => bs58::encode(&ser).into_string(),
+        }
+    }
+
+    pub fn decode(&self, s: &str) -> Option<VersionedTransaction> {
+        let ser = match self {
+            Self::Base64 => BASE64.decode(s.as_bytes()).ok()?,
+            Self::Base58 => bs58::decode(s).into_vec().ok()?,
+        };
+        bincode::deserialize(&ser).ok()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct IxJson {
+    #[serde(with = "b58pk")]
+    pub program_id: Pubkey,
+
+    pub accounts: Vec<AccountMetaJson>,
+
+    /// base64 encoded
+    pub data: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct AccountMetaJson {
+    #[serde(with = "b58pk")]
+    pub pubkey: Pubkey,
+
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+impl From<&Instruction> for IxJson {
+    fn from(
+        Instruction {
+            program_id,
+            accounts,
+            data,
+        }: &Instruction,
+    ) -> Self {
+        Self {
+            program_id: *program_id,
+            accounts: accounts
+                .iter()
+                .map(
+                    |AccountMeta {
+                         pubkey,
+                         is_signer,
+                         is_writable,
+                     }| AccountMetaJson {
+                        pubkey: *pubkey,
+                        is_signer: *is_signer,
+                        is_writable: *is_writable,
+                    },
+                )
+                .collect(),
+            data: BASE64.encode(data),
+        }
+    }
+}
+
+pub fn ixs_json(ixs: &[Instruction]) -> String {
+    serde_json::to_string_pretty(&ixs.iter().map(IxJson::from).collect::<Vec<_>>()).unwrap()
+}
+
+/// Recovers the instructions of a message that does not use address lookup tables.
+/// Signer and writable flags are those of the message,
+/// which may be more permissive than what the original instructions specified.
+pub fn decompile_ixs(msg: &VersionedMessage) -> Vec<Instruction> {
+    let keys = msg.static_account_keys();
+    msg.instructions()
+        .iter()
+        .map(|ix| Instruction {
+            program_id: keys[usize::from(ix.program_id_index)],
+            accounts: ix
+                .accounts
+                .iter()
+                .map(|i| {
+                    let i = usize::from(*i);
+                    AccountMeta {
+                        pubkey: keys[i],
+                        is_signer: msg.is_signer(i),
+                        is_writable: msg.is_maybe_writable(i, None),
+                    }
+                })
+                .collect(),
+            data: ix.data.clone(),
+        })
+        .collect()
+}
+
+/// `arg` is either a base64 or base58 serialized transaction,
+/// or a path to a file containing one
+pub fn parse_tx_arg(arg: &str) -> Result<(VersionedTransaction, TxEncoding), String> {
+    let s = if Path::new(arg).is_file() {
+        read_to_string(arg).map_err(|e| format!("Failed to read transaction file: {e}"))?
+    } else {
+        arg.to_owned()
+    };
+    let s = s.trim();
+    [TxEncoding::Base64, TxEncoding::Base58]
+        .into_iter()
+        .find_map(|enc| enc.decode(s).map(|tx| (tx, enc)))
+        .ok_or_else(|| "Failed to deserialize transaction from base64 or base58".to_owned())
+}
+
+/// Adds `signer`'s signature to `tx`, replacing any existing one.
+/// Errs if `signer` is not a required signer of `tx`.
+pub fn sign_tx(tx: &mut VersionedTransaction, signer: &dyn Signer) -> Result<(), String> {
+    let pk = signer.pubkey();
+    let n_signers = usize::from(tx.message.header().num_required_signatures);
+    let i = tx.message.static_account_keys()[..n_signers]
+        .iter()
+        .position(|k| *k == pk)
+        .ok_or_else(|| format!("{pk} is not a required signer of the transaction"))?;
+    tx.signatures[i] = signer
+        .try_sign_message(&tx.message.serialize())
+        .map_err(|e| format!("Failed to sign with {pk}: {e}"))?;
+    Ok(())
+}
+
+/// Required signers of `tx` whose signatures are missing or invalid, in order
+pub fn invalid_signers(tx: &VersionedTransaction) -> Vec<Pubkey> {
+    let msg = tx.message.serialize();
+    let n_signers = usize::from(tx.message.header().num_required_signatures);
+    tx.message.static_account_keys()[..n_signers]
+        .iter()
+        .zip(
+            tx.signatures
+                .iter()
+                .chain(core::iter::repeat(&Signature::default())),
+        )
+        .filter(|(pk, sig)| !sig.verify(pk.as_ref(), &msg))
+        .map(|(pk, _)| *pk)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_hash::Hash;
+    use solana_keypair::Keypair;
+    use solana_message::v0::Message;
+    use solana_signer::null_signer::NullSigner;
+
+    use super::*;
+
+    #[test]
+    fn sign_one_by_one() {
+        let [payer, admin] = core::array::from_fn(|_| Keypair::new());
+        let ix = Instruction::new_with_bytes(
+            Pubkey::new_unique(),
+            &[],
+            vec![AccountMeta::new_readonly(admin.pubkey(), true)],
+        );
+        let mut tx = VersionedTransaction::try_new(
+            VersionedMessage::V0(
+                Message::try_compile(&payer.pubkey(), &[ix], &[], Hash::default()).unwrap(),
+            ),
+            &[
+                &NullSigner::new(&payer.pubkey()) as &dyn Signer,
+                &NullSigner::new(&admin.pubkey()),
+            ],
+        )
+        .unwrap();
+        assert_eq!(invalid_signers(&tx), [payer.pubkey(), admin.pubkey()]);
+
+        // dumped and passed around between signers
+        for (enc, signer) in [(TxEncoding::Base64, &admin), (TxEncoding::Base58, &payer)] {
+            tx = enc.decode(&enc.encode(&tx)).unwrap();
+            sign_tx(&mut tx, signer).unwrap();
+        }
+        assert!(invalid_signers(&tx).is_empty());
+
+        assert!(sign_tx(&mut tx, &Keypair::new()).is_err());
+    }
+
+    #[test]
+    fn decompile_compile_roundtrip() {
+        let [payer, writable, readonly] = core::array::from_fn(|_| Pubkey::new_unique());
+        let ixs = [
+            Instruction::new_with_bytes(
+                Pubkey::new_unique(),
+                &[1, 2],
+                vec![
+                    AccountMeta::new(payer, true),
+                    AccountMeta::new(writable, false),
+                ],
+            ),
+            Instruction::new_with_bytes(
+                Pubkey::new_unique(),
+                &[3],
+                vec![
+                    AccountMeta::new_readonly(readonly, false),
+                    AccountMeta::new(writable, false),
+                ],
+            ),
+        ];
+        let msg =
+            VersionedMessage::V0(Message::try_compile(&payer, &ixs, &[], Hash::default()).unwrap());
+        assert_eq!(decompile_ixs(&msg), ixs);
+    }
+}
@@ -1,19 +1,28 @@
 use data_encoding::BASE64;
-use inf1_pp_flatslab_core::keys::SLAB_ID;
-use solana_commitment_config::{CommitmentConfig, CommitmentLevel};
+use inf1_pp_flatslab_core::{accounts::Slab, keys::SLAB_ID};
+use solana_commitment_config::CommitmentConfig;
 use solana_compute_budget_interface::ComputeBudgetInstruction;
 use solana_hash::Hash;
 use solana_instruction::{AccountMeta, Instruction};
-use solana_message::{VersionedMessage, v0::Message};
+use solana_message::{AddressLookupTableAccount, VersionedMessage, v0::Message};
 use solana_pubkey::Pubkey;
-use solana_rpc_client::{nonblocking::rpc_client::RpcClient, rpc_client::SerializableTransaction};
-use solana_rpc_client_types::config::{RpcSendTransactionConfig, RpcSimulateTransactionConfig};
+use solana_rpc_client_types::config::RpcSimulateTransactionConfig;
 use solana_signature::Signature;
 use solana_signer::Signer;
 use solana_transaction::versioned::VersionedTransaction;
 use solana_transaction_status_client_types::UiTransactionEncoding;
 
-use crate::sscu::TxSendMode;
+use crate::{
+    err::{CliErr, CliResult},
+    nonce::{DurableNonce, starts_with_advance_nonce},
+    priofee::PrioFee,
+    proposer::{Proposer, parse_payer},
+    rpc::Rpc,
+    sendloop::SendLoop,
+    simreport::print_sim_report,
+    sscu::{TxSendMode, parse_signer},
+    txser::{TxEncoding, decompile_ixs, ixs_json},
+};
 
 pub fn keys_signer_writable_to_metas<'a>(
     keys: impl Iterator<Item = &'a [u8; 32]>,
@@ -30,38 +39,58 @@ pub fn keys_signer_writable_to_metas<'a>(
         .collect()
 }
 
-/// Does nothing if `TxSendMode::DumpMsg`
+/// Does nothing if `send_mode` only dumps the transaction.
+///
+/// `luts` are only used to compile the simulated transaction so that it fits.
+///
+/// Compute budget instructions are inserted after the advance nonce instruction
+/// if `ixs` starts with one since that must remain the first instruction.
 pub async fn with_auto_cb(
     mut ixs: Vec<Instruction>,
     payer_pk: &Pubkey,
-    rpc: &RpcClient,
+    rpc: &dyn Rpc,
     tsm: TxSendMode,
-    fee_cb: u64,
-) -> Vec<Instruction> {
+    prio_fee: PrioFee,
+    luts: &[AddressLookupTableAccount],
+) -> CliResult<Vec<Instruction>> {
     match tsm {
-        TxSendMode::Dump64 | TxSendMode::Dump58 => ixs,
-        TxSendMode::SendActual | TxSendMode::SimOnly => {
+        TxSendMode::Dump64 | TxSendMode::Dump58 | TxSendMode::DumpMsg | TxSendMode::DumpIxs => {
+            Ok(ixs)
+        }
+        TxSendMode::SendActual | TxSendMode::SimOnly | TxSendMode::Squads | TxSendMode::Realms => {
             let result = rpc
-                .simulate_transaction_with_config(&to_est_cu_sim_tx(payer_pk, &ixs), SIM_TX_CFG)
-                .await
-                .unwrap();
-            let cus = result.value.units_consumed.unwrap();
+                .simulate_transaction_with_config(
+                    &to_est_cu_sim_tx(payer_pk, &ixs, luts),
+                    SIM_TX_CFG,
+                )
+                .await?;
+            let cus = result.value.units_consumed.ok_or_else(|| {
+                CliErr::Sim(format!(
+                    "Failed to estimate compute units: {:?}",
+                    result.value.err
+                ))
+            })?;
             let cus = (cus as f64 * 1.01) as u64 + 300;
-            let cu_price = (fee_cb * 1_000_000).div_ceil(cus);
+            let cu_price = prio_fee.cu_price(rpc, cus).await?;
+            let cb_idx = usize::from(starts_with_advance_nonce(&ixs));
             ixs.insert(
-                0,
+                cb_idx,
                 ComputeBudgetInstruction::set_compute_unit_limit(cus.try_into().unwrap()),
             );
             ixs.insert(
-                0,
+                cb_idx,
                 ComputeBudgetInstruction::set_compute_unit_price(cu_price),
             );
-            ixs
+            Ok(ixs)
         }
     }
 }
 
-fn to_est_cu_sim_tx(payer_pk: &Pubkey, ixs: &[Instruction]) -> VersionedTransaction {
+pub fn to_est_cu_sim_tx(
+    payer_pk: &Pubkey,
+    ixs: &[Instruction],
+    luts: &[AddressLookupTableAccount],
+) -> VersionedTransaction {
     // must set CU limit else default 200k will be used and expense txs will fail sim
     let ixs: Vec<_> = core::iter::once(ComputeBudgetInstruction::set_compute_unit_limit(
         1_400_000, // per tx cu limit
@@ -69,19 +98,79 @@ fn to_est_cu_sim_tx(payer_pk: &Pubkey, ixs: &[Instruction]) -> VersionedTransact
     .chain(ixs.iter().cloned())
     .collect();
     let message =
-        VersionedMessage::V0(Message::try_compile(payer_pk, &ixs, &[], Hash::default()).unwrap());
+        VersionedMessage::V0(Message::try_compile(payer_pk, &ixs, luts, Hash::default()).unwrap());
     VersionedTransaction {
         signatures: vec![Signature::default(); message.header().num_required_signatures.into()],
         message,
     }
 }
 
+/// Max size of a serialized transaction
+pub const MAX_TX_SIZE: usize = 1232;
+
+/// Serialized size of the transaction that results from running `ixs`
+/// through [`with_auto_cb`] and [`to_signed_tx`]
+pub fn est_tx_size(
+    payer_pk: &Pubkey,
+    ixs: &[Instruction],
+    luts: &[AddressLookupTableAccount],
+) -> Option<usize> {
+    // values dont matter, ix data is fixed size
+    let ixs: Vec<_> = [
+        ComputeBudgetInstruction::set_compute_unit_price(0),
+        ComputeBudgetInstruction::set_compute_unit_limit(0),
+    ]
+    .into_iter()
+    .chain(ixs.iter().cloned())
+    .collect();
+    let message =
+        VersionedMessage::V0(Message::try_compile(payer_pk, &ixs, luts, Hash::default()).ok()?);
+    let tx = VersionedTransaction {
+        signatures: vec![Signature::default(); message.header().num_required_signatures.into()],
+        message,
+    };
+    Some(bincode::serialized_size(&tx).unwrap().try_into().unwrap())
+}
+
+/// Packs `ixs`, preserving order, into as few transactions as possible
+/// such that each transaction, as sized by `est_size`, does not exceed [`MAX_TX_SIZE`].
+///
+/// Errs if a single instruction does not fit into a transaction.
+pub fn pack_ixs(
+    ixs: Vec<Instruction>,
+    est_size: impl Fn(&[Instruction]) -> Option<usize>,
+) -> CliResult<Vec<Vec<Instruction>>> {
+    let fits = |batch: &[Instruction]| est_size(batch).is_some_and(|size| size <= MAX_TX_SIZE);
+
+    let mut res = vec![];
+    let mut batch = vec![];
+    for ix in ixs {
+        batch.push(ix);
+        if fits(&batch) {
+            continue;
+        }
+        let ix = batch.pop().unwrap();
+        if batch.is_empty() {
+            return Err(CliErr::Input(format!(
+                "Instruction for program {} too large to fit into a transaction",
+                ix.program_id
+            )));
+        }
+        res.push(core::mem::replace(&mut batch, vec![ix]));
+    }
+    if !batch.is_empty() {
+        res.push(batch);
+    }
+    Ok(res)
+}
+
 /// First signer in signers is transaction payer
-pub async fn to_signed_tx(
+pub fn to_signed_tx(
     ixs: Vec<Instruction>,
     mut signers: Vec<&dyn Signer>,
-    rpc: &RpcClient,
-) -> VersionedTransaction {
+    rbh: Hash,
+    luts: &[AddressLookupTableAccount],
+) -> CliResult<VersionedTransaction> {
     let payer_pk = signers.first().unwrap().pubkey();
 
     // NB: sort only after picking out payer pubkey,
@@ -89,22 +178,219 @@ pub async fn to_signed_tx(
     signers.sort_by_key(|s| s.pubkey());
     signers.dedup_by_key(|s| s.pubkey());
 
-    let rbh = rpc.get_latest_blockhash().await.unwrap();
-
     VersionedTransaction::try_new(
-        VersionedMessage::V0(Message::try_compile(&payer_pk, &ixs, &[], rbh).unwrap()),
+        VersionedMessage::V0(Message::try_compile(&payer_pk, &ixs, luts, rbh).unwrap()),
         &signers,
     )
-    .unwrap()
+    .map_err(|e| CliErr::Config(format!("Failed to sign transaction: {e}")))
 }
 
-const SEND_TX_CFG: RpcSendTransactionConfig = RpcSendTransactionConfig {
-    skip_preflight: false,
-    preflight_commitment: Some(CommitmentLevel::Processed),
-    encoding: Some(UiTransactionEncoding::Base64),
-    max_retries: Some(0),
-    min_context_slot: None,
-};
+/// How transactions are built and what is done with them
+#[derive(Clone, Copy)]
+pub struct TxCtx<'a> {
+    pub rpc: &'a dyn Rpc,
+    pub send_mode: TxSendMode,
+    pub prio_fee: PrioFee,
+
+    /// Set if `send_mode` creates proposals
+    pub proposer: Option<&'a Proposer>,
+
+    pub nonce: Option<&'a DurableNonce>,
+
+    /// Address lookup table to compile transactions with
+    pub lut: Option<&'a AddressLookupTableAccount>,
+
+    pub send_loop: SendLoop,
+}
+
+impl<'a> TxCtx<'a> {
+    /// Runs `ixs` through [`with_auto_cb`] and [`to_signed_tx`].
+    /// First signer in signers is the payer of `ixs`.
+    ///
+    /// If `proposer` is set, `ixs` are instead wrapped into a new proposal
+    /// that is paid for and signed by only the proposer's signer.
+    ///
+    /// If `nonce` is set, the transaction advances it and uses its value as the blockhash.
+    pub async fn to_tx(
+        &self,
+        ixs: Vec<Instruction>,
+        signers: Vec<&dyn Signer>,
+    ) -> CliResult<VersionedTransaction> {
+        let (mut ixs, mut signers) = match self.proposer {
+            None => (ixs, signers),
+            Some(proposer) => (
+                proposer.wrap_ixs(self.rpc, &ixs).await?,
+                vec![proposer.signer()],
+            ),
+        };
+        if let Some(nonce) = self.nonce {
+            ixs.insert(0, nonce.advance_ix());
+            signers.push(nonce.authority.as_ref());
+        }
+        let payer_pk = signers.first().unwrap().pubkey();
+        let ixs = with_auto_cb(
+            ixs,
+            &payer_pk,
+            self.rpc,
+            self.send_mode,
+            self.prio_fee,
+            self.luts(),
+        )
+        .await?;
+        let rbh = match self.nonce {
+            Some(nonce) => nonce.fetch_blockhash(self.rpc).await?,
+            None => self.rpc.get_latest_blockhash().await?,
+        };
+        to_signed_tx(ixs, signers, rbh, self.luts())
+    }
+
+    /// [`est_tx_size`] of the transaction [`Self::to_tx`] would output
+    pub fn est_tx_size(&self, payer_pk: &Pubkey, ixs: &[Instruction]) -> Option<usize> {
+        let (payer_pk, ixs) = match self.proposer {
+            Some(proposer) => (proposer.signer().pubkey(), proposer.est_wrap_ixs(ixs)),
+            None => (*payer_pk, ixs.to_vec()),
+        };
+        let ixs: Vec<_> = self
+            .nonce
+            .map(DurableNonce::advance_ix)
+            .into_iter()
+            .chain(ixs)
+            .collect();
+        est_tx_size(&payer_pk, &ixs, self.luts())
+    }
+
+    /// Lookup tables to compile transactions with.
+    /// Empty for [`TxSendMode::DumpIxs`] since the tooling that runs dumped instructions
+    /// may not use the table, so batches must fit without it.
+    fn luts(&self) -> &'a [AddressLookupTableAccount] {
+        match (self.send_mode, self.lut) {
+            (TxSendMode::DumpIxs, _) | (_, None) => &[],
+            (_, Some(lut)) => core::slice::from_ref(lut),
+        }
+    }
+
+    /// Payer of the transaction [`Self::to_tx`] would output given the payer of the instructions
+    pub fn fee_payer(&self, payer_pk: &Pubkey) -> Pubkey {
+        match self.proposer {
+            Some(proposer) => proposer.signer().pubkey(),
+            None => *payer_pk,
+        }
+    }
+
+    /// Distinct pubkeys that sign the transaction [`Self::to_tx`] would output
+    /// given the signers of the instructions
+    pub fn signer_pks(&self, ix_signers: &[Pubkey]) -> Vec<Pubkey> {
+        let mut res = match self.proposer {
+            Some(proposer) => vec![proposer.signer().pubkey()],
+            None => ix_signers.to_vec(),
+        };
+        res.extend(self.nonce.map(|n| n.authority.pubkey()));
+        res.sort_unstable();
+        res.dedup();
+        res
+    }
+
+    /// Runs `ixs` through [`Self::to_tx`] and handles the transaction according to `send_mode`.
+    /// Sent transactions are rebuilt with a fresh blockhash if they expire without landing.
+    ///
+    /// [`TxSendMode::DumpIxs`] outputs `ixs` as is instead.
+    pub async fn send(&self, ixs: Vec<Instruction>, signers: Vec<&dyn Signer>) -> CliResult<()> {
+        match self.send_mode {
+            TxSendMode::SendActual | TxSendMode::Squads | TxSendMode::Realms => {
+                let sig = self
+                    .send_loop
+                    .send(self.rpc, async || {
+                        self.to_tx(ixs.clone(), signers.clone()).await
+                    })
+                    .await?;
+                if let Some(proposer) = self.proposer {
+                    proposer.advance();
+                }
+                print_sig(&sig);
+                Ok(())
+            }
+            // as given so that the tooling that runs them can add its own
+            // compute budget and nonce instructions
+            TxSendMode::DumpIxs => {
+                println!("{}", ixs_json(&ixs));
+                Ok(())
+            }
+            _ => {
+                let tx = self.to_tx(ixs, signers).await?;
+                handle_tx(self.rpc, self.send_mode, &self.send_loop, &tx).await
+            }
+        }
+    }
+}
+
+/// What a [`TxCtx`] borrows and who signs, parsed from [`crate::Args`]
+pub struct TxCtxSrc {
+    pub proposer: Option<Proposer>,
+    pub nonce: Option<DurableNonce>,
+    pub lut: Option<AddressLookupTableAccount>,
+
+    /// See [`parse_payer`]
+    pub payer: Box<dyn Signer>,
+
+    /// `--admin` of the subcommand if set
+    pub admin: Option<Box<dyn Signer>>,
+}
+
+impl TxCtxSrc {
+    pub async fn from_args(
+        crate::Args {
+            config,
+            send_mode,
+            squads,
+            realms,
+            nonce,
+            lut,
+            ..
+        }: &crate::Args,
+        admin: Option<&str>,
+        rpc: &dyn Rpc,
+    ) -> CliResult<Self> {
+        let proposer = Proposer::from_args(config, *send_mode, squads, realms)?;
+        let payer = parse_payer(config, proposer.as_ref())?;
+        Ok(Self {
+            nonce: nonce.to_nonce(config)?,
+            lut: lut.fetch(rpc).await?,
+            admin: admin
+                .map(parse_signer)
+                .transpose()
+                .map_err(CliErr::Config)?,
+            proposer,
+            payer,
+        })
+    }
+
+    /// `--admin` if set, otherwise the payer
+    pub fn admin(&self) -> &dyn Signer {
+        self.admin.as_deref().unwrap_or(self.payer.as_ref())
+    }
+
+    pub fn ctx<'a>(
+        &'a self,
+        crate::Args {
+            send_mode,
+            fee_cb,
+            prio_fee,
+            send_loop,
+            ..
+        }: &crate::Args,
+        rpc: &'a dyn Rpc,
+    ) -> TxCtx<'a> {
+        TxCtx {
+            rpc,
+            send_mode: *send_mode,
+            prio_fee: prio_fee.to_prio_fee(*fee_cb),
+            proposer: self.proposer.as_ref(),
+            nonce: self.nonce.as_ref(),
+            lut: self.lut.as_ref(),
+            send_loop: send_loop.to_send_loop(),
+        }
+    }
+}
 
 pub const SIM_TX_CFG: RpcSimulateTransactionConfig = RpcSimulateTransactionConfig {
     sig_verify: false,
@@ -116,40 +402,202 @@ pub const SIM_TX_CFG: RpcSimulateTransactionConfig = RpcSimulateTransactionConfi
     inner_instructions: true,
 };
 
-pub async fn handle_tx(rpc: &RpcClient, send_mode: TxSendMode, tx: &impl SerializableTransaction) {
+pub async fn handle_tx(
+    rpc: &dyn Rpc,
+    send_mode: TxSendMode,
+    send_loop: &SendLoop,
+    tx: &VersionedTransaction,
+) -> CliResult<()> {
     match send_mode {
-        TxSendMode::SendActual => {
-            let sig = rpc
-                .send_and_confirm_transaction_with_spinner_and_config(
-                    tx,
-                    CommitmentConfig::processed(),
-                    SEND_TX_CFG,
-                )
-                .await
-                .unwrap();
-            eprintln!("Signature:");
-            eprintln!("{sig}");
+        TxSendMode::SendActual | TxSendMode::Squads | TxSendMode::Realms => {
+            print_sig(&send_loop.send_signed(rpc, tx).await?);
         }
         TxSendMode::SimOnly => {
-            let result = rpc
-                .simulate_transaction_with_config(tx, SIM_TX_CFG)
-                .await
-                .unwrap();
-            eprintln!("Simulate result:");
-            eprintln!("{result:#?}");
-        }
-        TxSendMode::Dump64 => {
-            println!("{}", BASE64.encode(&bincode::serialize(tx).unwrap()))
-        }
-        TxSendMode::Dump58 => {
-            println!(
-                "{}",
-                bs58::encode(&bincode::serialize(tx).unwrap()).into_string()
-            )
+            let result = rpc.simulate_transaction_with_config(tx, SIM_TX_CFG).await?;
+            print_sim_report(&tx.message, &result.value);
+            if result.value.err.is_some() {
+                return Err(CliErr::Sim("Simulation failed".to_owned()));
+            }
         }
+        TxSendMode::Dump64 => println!("{}", TxEncoding::Base64.encode(tx)),
+        TxSendMode::Dump58 => println!("{}", TxEncoding::Base58.encode(tx)),
+        TxSendMode::DumpMsg => println!("{}", BASE64.encode(&tx.message.serialize())),
+        TxSendMode::DumpIxs => println!("{}", ixs_json(&decompile_ixs(&tx.message))),
     }
+    Ok(())
+}
+
+fn print_sig(sig: &Signature) {
+    eprintln!("Signature:");
+    eprintln!("{sig}");
 }
 
-pub async fn fetch_slab_data(rpc: &RpcClient) -> Vec<u8> {
-    rpc.get_account_data(&SLAB_ID.into()).await.unwrap()
+/// Fetches at the rpc's commitment.
+/// Errs if the slab does not exist or its data is not that of a slab
+/// so callers can unwrap [`Slab::of_acc_data`] on it
+pub async fn fetch_slab_data(rpc: &dyn Rpc) -> CliResult<Vec<u8>> {
+    fetch_slab_data_with_slot(rpc, rpc.commitment())
+        .await
+        .map(|(_, data)| data)
+}
+
+/// Returns (slot the data was fetched at, slab account data).
+/// Errs same as [`fetch_slab_data`].
+pub async fn fetch_slab_data_with_slot(
+    rpc: &dyn Rpc,
+    commitment: CommitmentConfig,
+) -> CliResult<(u64, Vec<u8>)> {
+    let res = rpc
+        .get_account_data_with_commitment(&SLAB_ID.into(), commitment)
+        .await?;
+    let data = res
+        .value
+        .ok_or_else(|| CliErr::Config("Slab account does not exist".to_owned()))?;
+    check_slab_data(&data)?;
+    Ok((res.context.slot, data))
+}
+
+/// Errs if `data` is not that of a slab
+pub fn check_slab_data(data: &[u8]) -> CliResult<()> {
+    Slab::of_acc_data(data).map(|_| ()).ok_or_else(|| {
+        CliErr::Config(format!(
+            "Account {} is not a valid slab",
+            Pubkey::new_from_array(SLAB_ID)
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{rpc::mock::MockRpc, syncplan::SyncOp};
+
+    use super::*;
+
+    fn mixed_ops_ixs(n: usize, payer: &Pubkey) -> Vec<Instruction> {
+        let admin = Pubkey::new_unique();
+        (0..n)
+            .map(|i| {
+                let mint = Pubkey::new_unique();
+                if i % 3 == 0 {
+                    SyncOp::RemoveLst { mint }
+                } else {
+                    SyncOp::SetLstFee {
+                        mint,
+                        inp_fee_nanos: 0,
+                        out_fee_nanos: 0,
+                    }
+                }
+            })
+            .map(|op| op.to_ix(admin.as_array(), payer.as_array()))
+            .collect()
+    }
+
+    #[test]
+    fn pack_ixs_fills_txs() {
+        let payer = Pubkey::new_unique();
+        let ixs = mixed_ops_ixs(100, &payer);
+        let batches = pack_ixs(ixs.clone(), |b| est_tx_size(&payer, b, &[])).unwrap();
+
+        assert_eq!(batches.concat(), ixs);
+        batches.iter().for_each(|b| {
+            let size = est_tx_size(&payer, b, &[]).unwrap();
+            assert!(size <= MAX_TX_SIZE, "{size}");
+        });
+        // every tx but the last must be full
+        batches.windows(2).for_each(|w| {
+            let mut b = w[0].clone();
+            b.push(w[1][0].clone());
+            assert!(est_tx_size(&payer, &b, &[]).is_none_or(|size| size > MAX_TX_SIZE));
+        });
+    }
+
+    #[test]
+    fn pack_ixs_lut_fits_more() {
+        let payer = Pubkey::new_unique();
+        let ixs = mixed_ops_ixs(100, &payer);
+        let mut addresses: Vec<_> = ixs
+            .iter()
+            .flat_map(|ix| {
+                core::iter::once(ix.program_id).chain(
+                    ix.accounts
+                        .iter()
+                        .filter(|a| !a.is_signer)
+                        .map(|a| a.pubkey),
+                )
+            })
+            .collect();
+        addresses.sort_unstable();
+        addresses.dedup();
+        let lut = AddressLookupTableAccount {
+            key: Pubkey::new_unique(),
+            addresses,
+        };
+
+        let without = pack_ixs(ixs.clone(), |b| est_tx_size(&payer, b, &[])).unwrap();
+        let with = pack_ixs(ixs.clone(), |b| {
+            est_tx_size(&payer, b, core::slice::from_ref(&lut))
+        })
+        .unwrap();
+        assert_eq!(with.concat(), ixs);
+        assert!(
+            with.len() < without.len(),
+            "{} {}",
+            with.len(),
+            without.len()
+        );
+    }
+
+    #[test]
+    fn pack_ixs_empty() {
+        assert!(
+            pack_ixs(vec![], |b| est_tx_size(&Pubkey::new_unique(), b, &[]))
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn pack_ixs_too_large() {
+        let payer = Pubkey::new_unique();
+        let ix = Instruction::new_with_bytes(Pubkey::new_unique(), &[0; MAX_TX_SIZE], vec![]);
+        assert!(matches!(
+            pack_ixs(vec![ix], |b| est_tx_size(&payer, b, &[])),
+            Err(CliErr::Input(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn fetch_slab_data_mock() {
+        let admin = Pubkey::new_unique();
+        assert!(matches!(
+            fetch_slab_data(&MockRpc::default()).await,
+            Err(CliErr::Config(_))
+        ));
+        assert!(matches!(
+            fetch_slab_data(&MockRpc::default().with_account(SLAB_ID.into(), vec![1, 2, 3])).await,
+            Err(CliErr::Config(_))
+        ));
+        assert_eq!(
+            fetch_slab_data(&MockRpc::default().with_slab(&admin, &[]))
+                .await
+                .unwrap(),
+            crate::rpc::mock::mock_slab_data(&admin, &[])
+        );
+    }
+
+    #[tokio::test]
+    async fn fetch_slab_data_with_slot_mock() {
+        let fetch = async |rpc: MockRpc| {
+            fetch_slab_data_with_slot(&rpc, CommitmentConfig::confirmed()).await
+        };
+
+        assert!(matches!(
+            fetch(MockRpc::default()).await,
+            Err(CliErr::Config(_))
+        ));
+        assert!(matches!(
+            fetch(MockRpc::default().with_account(SLAB_ID.into(), vec![1, 2, 3])).await,
+            Err(CliErr::Config(_))
+        ));
+    }
 }
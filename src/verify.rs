@@ -0,0 +1,380 @@
+use std::{collections::HashMap, fs::File, io::BufReader, path::Path, sync::Arc};
+
+use clap::{Args, builder::ValueParser};
+use serde::Deserialize;
+use solana_pubkey::Pubkey;
+
+use crate::{
+    err::{CliErr, CliResult},
+    slabcsv::{SlabCsvEntry, b58pk, parse_nanos},
+    slabdiff::SlabEntrySet,
+};
+
+#[derive(Args, Debug)]
+pub struct VerifyArgs {
+    #[arg(
+        long,
+        short,
+        help = "If flag set, skip verification step that verifies that no (inp, out) pair of the resulting slab entries results in a negative fee before running the operation.",
+        default_value_t = false
+    )]
+    pub no_verify: bool,
+
+    #[arg(
+        long,
+        value_parser = ValueParser::new(parse_nanos),
+        default_value = "1%",
+        help = "Max change a single sync may make to a fee, in raw nanos or `{decimal}%`"
+    )]
+    pub max_delta: i32,
+
+    #[arg(
+        long,
+        help = "Max change a single sync may make to a fee relative to its current value, in percent. Not applied to added mints or fees that are currently 0."
+    )]
+    pub max_rel_change: Option<f64>,
+
+    #[arg(
+        long,
+        help = "Path to csv of per-mint overrides of --max-delta and --max-rel-change with columns mint,max_delta,max_rel_change. Empty cells fall back to the flag values."
+    )]
+    pub limits: Option<Arc<str>>,
+
+    #[arg(
+        long,
+        help = "If flag set, runs even if some fee changes exceed the limits set by --max-delta, --max-rel-change and --limits",
+        default_value_t = false
+    )]
+    pub force: bool,
+}
+
+impl VerifyArgs {
+    /// Errs if `projected`, the slab entries that result from the operation,
+    /// have negative fee pairs unless `--no-verify`,
+    /// or if `sets` exceed the fee limits unless `--force`
+    pub fn check(&self, projected: &[SlabCsvEntry], sets: &[SlabEntrySet]) -> CliResult<()> {
+        let Self {
+            no_verify,
+            max_delta,
+            max_rel_change,
+            limits,
+            force,
+        } = self;
+
+        if !*no_verify {
+            let neg = negative_fee_pairs(projected);
+            if !neg.is_empty() {
+                for NegativeFeePair {
+                    inp,
+                    out,
+                    fee_nanos,
+                } in neg
+                {
+                    eprintln!("inp={inp}, out={out} results in fee={fee_nanos} < 0");
+                }
+                return Err(CliErr::Verify(
+                    "Resulting slab entries have negative fee pairs".to_owned(),
+                ));
+            }
+        }
+
+        if !*force {
+            let overrides = limits
+                .as_ref()
+                .map(|p| read_fee_limits_csv_file(p.as_ref()))
+                .transpose()?
+                .unwrap_or_default();
+            let violations = limit_violations(
+                sets,
+                FeeLimits {
+                    max_delta: Some(*max_delta),
+                    max_rel_change_pct: *max_rel_change,
+                },
+                &overrides,
+            );
+            if !violations.is_empty() {
+                for LimitViolation {
+                    mint,
+                    fee,
+                    old,
+                    new,
+                } in violations
+                {
+                    let old = old.map_or_else(|| "-".to_owned(), |o| o.to_string());
+                    eprintln!("{mint} {fee}: {old} -> {new} exceeds limits");
+                }
+                return Err(CliErr::Verify(
+                    "Fee changes exceed limits, rerun with --force to run anyway".to_owned(),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// An (inp, out) pair whose total fee is negative,
+/// i.e. swapping `inp` for `out` gives the user more SOL value than they put in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NegativeFeePair {
+    pub inp: Pubkey,
+    pub out: Pubkey,
+    pub fee_nanos: i64,
+}
+
+/// Returns every (inp, out) pair of `entries` that results in a negative fee
+pub fn negative_fee_pairs(entries: &[SlabCsvEntry]) -> Vec<NegativeFeePair> {
+    entries
+        .iter()
+        .flat_map(|inp| {
+            entries.iter().filter_map(move |out| {
+                // i64 to avoid overflow
+                let fee_nanos = i64::from(inp.inp) + i64::from(out.out);
+                (fee_nanos < 0).then_some(NegativeFeePair {
+                    inp: inp.mint,
+                    out: out.mint,
+                    fee_nanos,
+                })
+            })
+        })
+        .collect()
+}
+
+/// Limits on how far a single sync may move a fee
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FeeLimits {
+    /// Max absolute change in nanos
+    pub max_delta: Option<i32>,
+
+    /// Max change relative to the current fee, in percent.
+    /// Not applied to added mints or fees that are currently 0.
+    pub max_rel_change_pct: Option<f64>,
+}
+
+impl FeeLimits {
+    /// Falls back to `default` for each limit that is not set
+    pub fn or(self, default: Self) -> Self {
+        Self {
+            max_delta: self.max_delta.or(default.max_delta),
+            max_rel_change_pct: self.max_rel_change_pct.or(default.max_rel_change_pct),
+        }
+    }
+
+    pub fn is_exceeded(&self, old: Option<i32>, new: i32) -> bool {
+        let old = old.map(i64::from);
+        let delta = (i64::from(new) - old.unwrap_or_default()).abs();
+        let exceeds_abs = self
+            .max_delta
+            .is_some_and(|max| delta > i64::from(max).abs());
+        let exceeds_rel = match (old, self.max_rel_change_pct) {
+            (Some(old), Some(max_pct)) if old != 0 => {
+                delta as f64 / old.abs() as f64 * 100.0 > max_pct
+            }
+            _ => false,
+        };
+        exceeds_abs || exceeds_rel
+    }
+}
+
+/// A fee change that exceeds its [`FeeLimits`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LimitViolation {
+    pub mint: Pubkey,
+
+    /// `"inp"` or `"out"`
+    pub fee: &'static str,
+
+    /// `None` if mint is being added
+    pub old: Option<i32>,
+
+    pub new: i32,
+}
+
+/// Returns every fee change of `sets` that exceeds its limits.
+/// `overrides` replaces `default` for its mints, falling back to `default` for unset limits.
+pub fn limit_violations(
+    sets: &[SlabEntrySet],
+    default: FeeLimits,
+    overrides: &HashMap<Pubkey, FeeLimits>,
+) -> Vec<LimitViolation> {
+    sets.iter()
+        .flat_map(|SlabEntrySet { old, new }| {
+            let mint = new.mint;
+            let limits = overrides.get(&mint).map_or(default, |o| o.or(default));
+            [
+                ("inp", old.map(|o| o.inp), new.inp),
+                ("out", old.map(|o| o.out), new.out),
+            ]
+            .into_iter()
+            .filter(move |(_, old, new)| limits.is_exceeded(*old, *new))
+            .map(move |(fee, old, new)| LimitViolation {
+                mint,
+                fee,
+                old,
+                new,
+            })
+        })
+        .collect()
+}
+
+#[derive(Deserialize)]
+struct FeeLimitsCsvRow {
+    #[serde(with = "b58pk")]
+    mint: Pubkey,
+    max_delta: Option<String>,
+    max_rel_change: Option<f64>,
+}
+
+/// Reads per-mint [`FeeLimits`] overrides from a csv with columns `mint,max_delta,max_rel_change`.
+/// Empty cells fall back to the default limits.
+pub fn read_fee_limits_csv_file(p: impl AsRef<Path>) -> CliResult<HashMap<Pubkey, FeeLimits>> {
+    let f = BufReader::new(
+        File::open(p)
+            .map_err(|e| CliErr::Input(format!("Failed to read fee limits csv file: {e}")))?,
+    );
+    csv::Reader::from_reader(f)
+        .deserialize()
+        .map(|r| {
+            let FeeLimitsCsvRow {
+                mint,
+                max_delta,
+                max_rel_change,
+            } = r.map_err(|e| {
+                CliErr::Input(format!("Failed to deserialize fee limits entry: {e}"))
+            })?;
+            let max_delta = max_delta
+                .filter(|s| !s.is_empty())
+                .map(|s| parse_nanos(&s))
+                .transpose()
+                .map_err(|e| CliErr::Input(format!("Invalid max_delta for {mint}: {e}")))?;
+            Ok((
+                mint,
+                FeeLimits {
+                    max_delta,
+                    max_rel_change_pct: max_rel_change,
+                },
+            ))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_all_negative_pairs() {
+        let [a, b, c] = core::array::from_fn(|_| Pubkey::new_unique());
+        let entries = [
+            SlabCsvEntry {
+                mint: a,
+                inp: -3,
+                out: 1,
+            },
+            SlabCsvEntry {
+                mint: b,
+                inp: 2,
+                out: 2,
+            },
+            SlabCsvEntry {
+                mint: c,
+                inp: 0,
+                out: -1,
+            },
+        ];
+        assert_eq!(
+            negative_fee_pairs(&entries),
+            [
+                NegativeFeePair {
+                    inp: a,
+                    out: a,
+                    fee_nanos: -2,
+                },
+                NegativeFeePair {
+                    inp: a,
+                    out: c,
+                    fee_nanos: -4,
+                },
+                NegativeFeePair {
+                    inp: c,
+                    out: c,
+                    fee_nanos: -1,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn no_overflow() {
+        let mint = Pubkey::new_unique();
+        assert_eq!(
+            negative_fee_pairs(&[SlabCsvEntry {
+                mint,
+                inp: i32::MIN,
+                out: i32::MIN,
+            }]),
+            [NegativeFeePair {
+                inp: mint,
+                out: mint,
+                fee_nanos: 2 * i64::from(i32::MIN),
+            }]
+        );
+    }
+
+    #[test]
+    fn limit_violations_abs_rel_and_overrides() {
+        let [a, b, c] = core::array::from_fn(|_| Pubkey::new_unique());
+        let entry = |mint, inp, out| SlabCsvEntry { mint, inp, out };
+        let sets = [
+            // inp exceeds max_delta, out exceeds max_rel_change
+            SlabEntrySet {
+                old: Some(entry(a, 1_000_000, 1_000_000)),
+                new: entry(a, 20_000_000, 1_600_000),
+            },
+            // added mint, only max_delta applies
+            SlabEntrySet {
+                old: None,
+                new: entry(b, 2_000_000, 20_000_000),
+            },
+            // exceeds default max_delta but overridden
+            SlabEntrySet {
+                old: Some(entry(c, 0, 0)),
+                new: entry(c, 20_000_000, 0),
+            },
+        ];
+        let default = FeeLimits {
+            max_delta: Some(10_000_000),
+            max_rel_change_pct: Some(50.0),
+        };
+        let overrides = HashMap::from([(
+            c,
+            FeeLimits {
+                max_delta: Some(30_000_000),
+                max_rel_change_pct: None,
+            },
+        )]);
+
+        assert_eq!(
+            limit_violations(&sets, default, &overrides),
+            [
+                LimitViolation {
+                    mint: a,
+                    fee: "inp",
+                    old: Some(1_000_000),
+                    new: 20_000_000,
+                },
+                LimitViolation {
+                    mint: a,
+                    fee: "out",
+                    old: Some(1_000_000),
+                    new: 1_600_000,
+                },
+                LimitViolation {
+                    mint: b,
+                    fee: "out",
+                    old: None,
+                    new: 20_000_000,
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,12 @@
+#!/usr/bin/env bash
+# Dumps the mainnet program binaries the e2e tests in src/subcmd/e2e.rs run.
+#
+# Usage: test-fixtures/dump-programs.sh
+
+set -euo pipefail
+
+cd "$(dirname "$0")"
+
+# inf1_pp_flatslab_core::ID
+solana program dump -um s1b6NRXj6ygNu1QMKXh2H9LUR2aPApAAm1UQ2DjdhNV flatslab.so
+solana program dump -um SQDS4ep65T869zMMBKyuUq6aD6EgTu8psMjkvj52pCf squads.so